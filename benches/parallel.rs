@@ -0,0 +1,38 @@
+// benches/parallel.rs
+//
+// Sequential vs parallel depth-first generation of a 1000x1000 maze.
+// Run with `cargo bench --bench parallel`.
+
+use krunker_maze_generator::Maze;
+use std::time::{Duration, Instant};
+
+const SIZE: usize = 1000;
+const RUNS: u32 = 5;
+
+/// Mean wall-clock time of `RUNS` generations of a fresh maze
+fn time(mut generate: impl FnMut(&mut Maze)) -> Duration {
+    let mut total = Duration::ZERO;
+    for seed in 0..RUNS {
+        let mut maze = Maze::new(SIZE, SIZE).expect("non-zero dimensions");
+        maze.seed = u64::from(seed);
+        let start = Instant::now();
+        generate(&mut maze);
+        total += start.elapsed();
+    }
+    total / RUNS
+}
+
+fn main() {
+    println!("{SIZE}x{SIZE}, mean of {RUNS} runs");
+    println!("sequential:   {:?}", time(Maze::generate));
+    let cores = std::thread::available_parallelism().map_or(4, |n| n.get());
+    let mut counts = vec![2, 4, cores];
+    counts.sort_unstable();
+    counts.dedup();
+    for threads in counts {
+        println!(
+            "{threads:>2} threads:   {:?}",
+            time(|maze| maze.generate_parallel(threads))
+        );
+    }
+}
@@ -0,0 +1,3 @@
+// examples/wasm/lib.rs
+
+pub use krunker_maze_generator::WasmMaze;
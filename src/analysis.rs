@@ -0,0 +1,663 @@
+// src/analysis.rs
+
+use crate::{Maze, MazeBuilder, MazeError};
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use serde::Serialize;
+
+/// Summary numbers for comparing mazes and generation algorithms
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MazeStats {
+    /// Cells with exactly one open passage
+    pub dead_end_count: usize,
+    /// Cells with three or more open passages
+    pub junction_count: usize,
+    /// Moves along the start-to-end solution (0 if unsolvable)
+    pub solution_length: usize,
+    /// Moves along `Maze::longest_path`
+    pub longest_path_length: usize,
+    /// Independent loops in the passage graph (0 for a perfect maze)
+    pub cycle_count: usize,
+    /// Average number of open passages per cell, ignoring the outer ring
+    /// of cells (0.0 when there are no interior cells)
+    pub branch_factor: f64,
+    /// Average moves from a dead end back to the nearest junction
+    /// (0.0 without dead ends)
+    pub dead_end_depth: f64,
+    /// `Maze::difficulty_score`
+    pub difficulty: f64,
+    /// `solution_length / longest_path_length`, capped at 1.0 (0.0 when
+    /// either is 0): near 1.0 the solution is the maze's longest route,
+    /// near 0.0 it is a short hop across a much larger maze.
+    pub river_factor: f64,
+}
+
+/// BFS distances (`usize::MAX` = unreachable) and parent links from one cell
+pub(crate) struct BfsTree {
+    pub dist: Vec<Vec<usize>>,
+    pub parent: Vec<Vec<Option<(usize, usize)>>>,
+}
+
+impl BfsTree {
+    /// Cells from the BFS root to `target`, or empty if unreachable
+    pub fn path_to(&self, target: (usize, usize)) -> Vec<(usize, usize)> {
+        if self.dist[target.1][target.0] == usize::MAX {
+            return Vec::new();
+        }
+        let mut path = vec![target];
+        let mut cur = target;
+        while let Some(p) = self.parent[cur.1][cur.0] {
+            path.push(p);
+            cur = p;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Loop formed by the non-tree edge `u`–`v`: up from `u` to the common
+    /// ancestor, then back down to `v`
+    pub fn cycle_through(&self, u: (usize, usize), v: (usize, usize)) -> Vec<(usize, usize)> {
+        let (mut a, mut b) = (u, v);
+        let (mut up, mut down) = (vec![a], vec![b]);
+        while a != b {
+            if self.dist[a.1][a.0] >= self.dist[b.1][b.0] {
+                a = self.parent[a.1][a.0].unwrap_or(a);
+                up.push(a);
+            } else {
+                b = self.parent[b.1][b.0].unwrap_or(b);
+                down.push(b);
+            }
+        }
+        down.pop();
+        up.extend(down.into_iter().rev());
+        up
+    }
+
+    /// Reachable cell with the greatest distance (first one in row order on ties)
+    pub fn farthest(&self) -> (usize, usize) {
+        let mut best = ((0, 0), 0);
+        for (y, row) in self.dist.iter().enumerate() {
+            for (x, &d) in row.iter().enumerate() {
+                if d != usize::MAX && d > best.1 {
+                    best = ((x, y), d);
+                }
+            }
+        }
+        best.0
+    }
+}
+
+// Graph measurements over the open passages
+impl Maze {
+    pub(crate) fn bfs_tree(&self, start: (usize, usize)) -> BfsTree {
+        self.bfs_over(start, |cell| self.open_neighbors(cell))
+    }
+
+    /// BFS from `start` following `next` from each cell: `open_neighbors`
+    /// for the layout itself, or `moves` to honour one-way passages
+    pub(crate) fn bfs_over(
+        &self,
+        start: (usize, usize),
+        next: impl Fn((usize, usize)) -> Vec<(usize, usize)>,
+    ) -> BfsTree {
+        let mut dist = vec![vec![usize::MAX; self.width]; self.height];
+        let mut parent = vec![vec![None; self.width]; self.height];
+        if !self.in_bounds(start) {
+            return BfsTree { dist, parent };
+        }
+        dist[start.1][start.0] = 0;
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            let d = dist[cell.1][cell.0];
+            for n in next(cell) {
+                if dist[n.1][n.0] == usize::MAX {
+                    dist[n.1][n.0] = d + 1;
+                    parent[n.1][n.0] = Some(cell);
+                    queue.push_back(n);
+                }
+            }
+        }
+        BfsTree { dist, parent }
+    }
+
+    /// BFS distance (in moves) from `start` to every cell, indexed `[y][x]`;
+    /// `usize::MAX` marks cells that cannot be reached
+    pub fn distances_from(&self, start: (usize, usize)) -> Vec<Vec<usize>> {
+        self.bfs_tree(start).dist
+    }
+
+    /// The path between the two cells farthest apart (the maze's diameter),
+    /// found with two BFS passes: from `self.start` to the farthest cell `a`,
+    /// then from `a` to the cell farthest from it. Exact for perfect mazes;
+    /// with loops it is a good lower bound.
+    pub fn longest_path(&self) -> Vec<(usize, usize)> {
+        if !self.in_bounds(self.start) {
+            return Vec::new();
+        }
+        let a = self.bfs_tree(self.start).farthest();
+        let tree = self.bfs_tree(a);
+        tree.path_to(tree.farthest())
+    }
+
+    /// Dead ends that can be reached from `start`, farthest first (cells at
+    /// the same distance stay in row order). `start` itself is left out.
+    /// Handy for placing objectives or spawns; in masked or braided mazes
+    /// some dead ends may not be reachable at all.
+    pub fn reachable_dead_ends(&self, start: (usize, usize)) -> Vec<(usize, usize)> {
+        if !self.in_bounds(start) {
+            return Vec::new();
+        }
+        let dist = self.distances_from(start);
+        let mut out: Vec<_> = self
+            .dead_ends()
+            .into_iter()
+            .filter(|&(x, y)| (x, y) != start && dist[y][x] != usize::MAX)
+            .collect();
+        out.sort_by_key(|&(x, y)| core::cmp::Reverse(dist[y][x]));
+        out
+    }
+
+    /// Number of open passages leaving a cell
+    pub(crate) fn degree(&self, cell: (usize, usize)) -> usize {
+        self.open_neighbors(cell).len()
+    }
+
+    /// Every cell with exactly one open passage, in row order
+    pub fn dead_ends(&self) -> Vec<(usize, usize)> {
+        let mut out = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.degree((x, y)) == 1 {
+                    out.push((x, y));
+                }
+            }
+        }
+        out
+    }
+
+    /// Standing interior walls: walls between two cells, leaving out the
+    /// outer boundary. On a toroidal maze the wrap-around walls are
+    /// interior and count once each. `wall_count() + passage_count()` is
+    /// the number of places a wall can stand between cells.
+    pub fn wall_count(&self) -> usize {
+        let mut slots = (self.width - 1) * self.height + self.width * (self.height - 1);
+        if self.wraps_x() {
+            slots += self.height;
+        }
+        if self.wraps_y() {
+            slots += self.width;
+        }
+        slots - self.passage_count()
+    }
+
+    /// Removed interior walls, i.e. edges of the passage graph. On a
+    /// toroidal maze the open wrap-around walls count as well. A perfect
+    /// maze has exactly `width * height - 1`.
+    pub fn passage_count(&self) -> usize {
+        let vert: usize = self
+            .vert_walls
+            .iter()
+            .map(|row| row[1..self.width].iter().filter(|&&w| !w).count())
+            .sum();
+        let hor: usize = self.hor_walls[1..self.height]
+            .iter()
+            .map(|row| row.iter().filter(|&&w| !w).count())
+            .sum();
+        let wrap_vert = if self.wraps_x() {
+            self.vert_walls.iter().filter(|row| !row[0]).count()
+        } else {
+            0
+        };
+        let wrap_hor = if self.wraps_y() {
+            self.hor_walls[0].iter().filter(|&&w| !w).count()
+        } else {
+            0
+        };
+        vert + hor + wrap_vert + wrap_hor
+    }
+
+    /// Number of disconnected regions of cells
+    pub(crate) fn component_count(&self) -> usize {
+        let mut seen = vec![vec![false; self.width]; self.height];
+        let mut count = 0;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if seen[y][x] {
+                    continue;
+                }
+                count += 1;
+                seen[y][x] = true;
+                let mut stack = vec![(x, y)];
+                while let Some(cell) = stack.pop() {
+                    for n in self.open_neighbors(cell) {
+                        if !seen[n.1][n.0] {
+                            seen[n.1][n.0] = true;
+                            stack.push(n);
+                        }
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    /// Compute all `MazeStats` fields
+    pub fn statistics(&self) -> MazeStats {
+        let mut dead_end_count = 0;
+        let mut junction_count = 0;
+        let (mut interior_degree, mut interior_cells) = (0, 0);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let degree = self.degree((x, y));
+                match degree {
+                    1 => dead_end_count += 1,
+                    d if d >= 3 => junction_count += 1,
+                    _ => {}
+                }
+                if x > 0 && y > 0 && x + 1 < self.width && y + 1 < self.height {
+                    interior_degree += degree;
+                    interior_cells += 1;
+                }
+            }
+        }
+        let branch_factor = if interior_cells == 0 {
+            0.0
+        } else {
+            interior_degree as f64 / interior_cells as f64
+        };
+
+        // Cyclomatic number: edges beyond what a spanning forest needs
+        let cycle_count = (self.passage_count() + self.component_count())
+            .saturating_sub(self.width * self.height);
+
+        let depths: Vec<usize> = self
+            .dead_ends()
+            .into_iter()
+            .map(|cell| self.dead_end_depth(cell))
+            .collect();
+        let dead_end_depth = if depths.is_empty() {
+            0.0
+        } else {
+            depths.iter().sum::<usize>() as f64 / depths.len() as f64
+        };
+
+        let mut stats = MazeStats {
+            dead_end_count,
+            junction_count,
+            cycle_count,
+            solution_length: self.solve().map_or(0, |path| path.len() - 1),
+            longest_path_length: self.longest_path().len().saturating_sub(1),
+            branch_factor,
+            dead_end_depth,
+            difficulty: 0.0,
+            river_factor: 0.0,
+        };
+        if stats.longest_path_length > 0 {
+            stats.river_factor =
+                (stats.solution_length as f64 / stats.longest_path_length as f64).min(1.0);
+        }
+        stats.difficulty = self.difficulty_from(&stats);
+        stats
+    }
+
+    /// A single 0.0–1.0 difficulty rating, 1.0 being the hardest, for
+    /// picking easy, medium and hard maps automatically. It is a weighted
+    /// sum of six measures, each scaled to 0.0–1.0 first:
+    ///
+    /// - 0.40 × `1 - straight / solution_length`, where `straight` is the
+    ///   Manhattan distance from `start` to `end`: how far the solution
+    ///   strays from the direct line
+    /// - 0.20 × `min(4 × solution_length / (cells - 1), 1)`: how much of
+    ///   the maze the solution covers
+    /// - 0.20 × `dead_end_depth / (dead_end_depth + 2)`: long blind alleys
+    ///   cost more than short stubs
+    /// - 0.10 × `min(2 × dead_end_count / cells, 1)`
+    /// - 0.05 × `min(2 × junction_count / cells, 1)`: decisions to make
+    /// - 0.05 × `clamp((branch_factor - 2) / 2, 0, 1)`: openings beyond a
+    ///   corridor's two
+    ///
+    /// The measures follow M. S. McClendon, "The Complexity and Difficulty
+    /// of a Maze" (Bridges, 2001), which rates a maze by its solution and
+    /// the choices and dead ends met along it; the weights are this
+    /// crate's own. On 40x40 grids binary tree mazes score about 0.2,
+    /// Prim's 0.25–0.3, most other algorithms 0.3–0.5 and depth-first
+    /// mazes 0.5–0.7.
+    pub fn difficulty_score(&self) -> f64 {
+        self.statistics().difficulty
+    }
+
+    /// A new depth-first maze the size of this one (and as `toroidal`),
+    /// carved from `self.seed`, then `self.seed + 1` and so on until its
+    /// `difficulty_score` is at least `min`; see
+    /// `MazeBuilder::min_difficulty` for other algorithms. Fails with
+    /// `MazeError::DifficultyNotAchievable` after `max_attempts` tries.
+    pub fn generate_with_min_difficulty(
+        &self,
+        min: f64,
+        max_attempts: usize,
+    ) -> Result<Maze, MazeError> {
+        MazeBuilder::new()
+            .width(self.width)
+            .height(self.height)
+            .toroidal(self.toroidal)
+            .seed(self.seed)
+            .min_difficulty(min, max_attempts)
+            .build()
+    }
+
+    /// Carve this maze afresh as a depth-first maze from `self.seed`, then
+    /// `self.seed + 1` and so on, until the `solve` path takes between
+    /// `min_length` and `max_length` moves (inclusive); returns that
+    /// length. Size, `start`, `end` and `toroidal` are kept, and `seed`
+    /// becomes the one that worked; rooms, portals and other additions are
+    /// not carried over. Fails with `MazeError::PathLengthNotAchievable`
+    /// after `max_attempts` tries, leaving the maze unchanged.
+    pub fn generate_with_path_length(
+        &mut self,
+        min_length: usize,
+        max_length: usize,
+        max_attempts: usize,
+    ) -> Result<usize, MazeError> {
+        for attempt in 0..max_attempts {
+            let mut maze = Maze::new(self.width, self.height)?;
+            maze.start = self.start;
+            maze.end = self.end;
+            maze.toroidal = self.toroidal;
+            maze.generate_with_seed(self.seed.wrapping_add(attempt as u64));
+            let length = maze.solve().map_or(0, |path| path.len() - 1);
+            if (min_length..=max_length).contains(&length) {
+                *self = maze;
+                return Ok(length);
+            }
+        }
+        Err(MazeError::PathLengthNotAchievable)
+    }
+
+    /// `difficulty_score` from already computed statistics
+    fn difficulty_from(&self, stats: &MazeStats) -> f64 {
+        let cells = (self.width * self.height) as f64;
+        let length = stats.solution_length as f64;
+        let straight =
+            (self.start.0.abs_diff(self.end.0) + self.start.1.abs_diff(self.end.1)) as f64;
+        let detour = if length > 0.0 {
+            1.0 - straight.min(length) / length
+        } else {
+            0.0
+        };
+        let coverage = if cells > 1.0 {
+            (4.0 * length / (cells - 1.0)).min(1.0)
+        } else {
+            0.0
+        };
+        let depth = stats.dead_end_depth / (stats.dead_end_depth + 2.0);
+        let dead_ends = (2.0 * stats.dead_end_count as f64 / cells).min(1.0);
+        let junctions = (2.0 * stats.junction_count as f64 / cells).min(1.0);
+        let branching = ((stats.branch_factor - 2.0) / 2.0).clamp(0.0, 1.0);
+        (0.40 * detour
+            + 0.20 * coverage
+            + 0.20 * depth
+            + 0.10 * dead_ends
+            + 0.05 * junctions
+            + 0.05 * branching)
+            .clamp(0.0, 1.0)
+    }
+
+    /// Moves from a dead end along its corridor to the first cell with
+    /// three or more passages, or to the far end of a corridor that has
+    /// none
+    fn dead_end_depth(&self, dead_end: (usize, usize)) -> usize {
+        let (mut prev, mut cell) = (dead_end, dead_end);
+        let mut depth = 0;
+        loop {
+            let next = self.open_neighbors(cell).into_iter().find(|&n| n != prev);
+            match next {
+                Some(next) if depth == 0 || self.degree(cell) == 2 => {
+                    (prev, cell) = (cell, next);
+                    depth += 1;
+                }
+                _ => return depth,
+            }
+        }
+    }
+
+    /// Whether the maze is perfect: a spanning tree where every cell is
+    /// reachable from (0, 0) and exactly `width * height - 1` walls have
+    /// been opened, so any two cells are joined by exactly one path
+    pub fn is_perfect(&self) -> bool {
+        self.width * self.height > 0
+            && self.unreachable_cells().is_empty()
+            && self.passage_count() == self.width * self.height - 1
+    }
+
+    /// Cells that cannot be reached from (0, 0), in row order
+    pub fn unreachable_cells(&self) -> Vec<(usize, usize)> {
+        let tree = self.bfs_tree((0, 0));
+        let mut out = Vec::new();
+        for (y, row) in tree.dist.iter().enumerate() {
+            for (x, &d) in row.iter().enumerate() {
+                if d == usize::MAX {
+                    out.push((x, y));
+                }
+            }
+        }
+        out
+    }
+
+    /// Groups of cells joined by open passages, ordered by their first cell
+    /// in row order; within a group cells are in breadth-first order from
+    /// that cell. A perfect maze is a single group, while masks or manual
+    /// edits can leave isolated regions.
+    pub fn connected_components(&self) -> Vec<Vec<(usize, usize)>> {
+        let mut seen = vec![vec![false; self.width]; self.height];
+        let mut components = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if seen[y][x] {
+                    continue;
+                }
+                seen[y][x] = true;
+                let mut component = vec![(x, y)];
+                let mut next = 0;
+                while let Some(&cell) = component.get(next) {
+                    next += 1;
+                    for (nx, ny) in self.open_neighbors(cell) {
+                        if !seen[ny][nx] {
+                            seen[ny][nx] = true;
+                            component.push((nx, ny));
+                        }
+                    }
+                }
+                components.push(component);
+            }
+        }
+        components
+    }
+
+    /// Whether every cell can reach every other one
+    pub fn is_connected(&self) -> bool {
+        self.component_count() == 1
+    }
+
+    /// One loop in the passage graph as a closed walk of cells (the first
+    /// cell is not repeated at the end), or `None` if the maze has no loops
+    pub fn find_cycle(&self) -> Option<Vec<(usize, usize)>> {
+        let mut done = vec![vec![false; self.width]; self.height];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if done[y][x] {
+                    continue;
+                }
+                let tree = self.bfs_tree((x, y));
+                for (cy, row) in tree.dist.iter().enumerate() {
+                    for (cx, &d) in row.iter().enumerate() {
+                        if d == usize::MAX {
+                            continue;
+                        }
+                        done[cy][cx] = true;
+                        let u = (cx, cy);
+                        for v in self.open_neighbors(u) {
+                            // Any edge the BFS tree did not use closes a loop
+                            let tree_edge =
+                                tree.parent[v.1][v.0] == Some(u) || tree.parent[cy][cx] == Some(v);
+                            if !tree_edge {
+                                return Some(tree.cycle_through(u, v));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Number of distinct simple paths (no cell visited twice) from
+    /// `self.start` to `self.end` of at most `max_len` moves, found by
+    /// depth-limited depth-first search. Loopy mazes can have exponentially
+    /// many paths, so the search stops once `max` are found and never walks
+    /// further than `max_len`; the flag is true when either limit cut it
+    /// short, so there may be more. A perfect maze has exactly one.
+    pub fn count_solutions(&self, max: usize, max_len: usize) -> (usize, bool) {
+        self.simple_paths(max, max_len, |_| {})
+    }
+
+    /// Up to `max_paths` distinct simple paths from `self.start` to
+    /// `self.end`, sorted by length, each listing the cells from start to
+    /// end. A perfect maze has exactly one; a braided maze yields its
+    /// alternate routes. The search is the one behind `count_solutions`,
+    /// so `max_paths` also bounds the running time; with more routes than
+    /// that the paths kept are the first found, not the shortest overall.
+    pub fn solve_all_paths(&self, max_paths: usize) -> Vec<Vec<(usize, usize)>> {
+        let mut paths = Vec::new();
+        self.simple_paths(max_paths, usize::MAX, |path| paths.push(path.to_vec()));
+        paths.sort_by_key(Vec::len);
+        paths
+    }
+
+    /// Depth-first search over the simple paths from `start` to `end` of at
+    /// most `max_len` moves, handing each to `found` until `max` have been
+    /// seen; returns how many were found and whether either limit cut the
+    /// search short
+    fn simple_paths(
+        &self,
+        max: usize,
+        max_len: usize,
+        mut found: impl FnMut(&[(usize, usize)]),
+    ) -> (usize, bool) {
+        if !self.in_bounds(self.start) || !self.in_bounds(self.end) {
+            return (0, false);
+        }
+        if max == 0 {
+            return (0, true);
+        }
+        if self.start == self.end {
+            found(&[self.start]);
+            return (1, max == 1);
+        }
+        let mut on_path = vec![vec![false; self.width]; self.height];
+        on_path[self.start.1][self.start.0] = true;
+        let mut path = vec![self.start];
+        let mut stack = vec![self.moves(self.start)];
+        let mut count = 0;
+        let mut cut = false;
+        while let Some(untried) = stack.last_mut() {
+            // Moves the path would have after one more step
+            let moves = path.len();
+            match untried.pop() {
+                Some(next) if next == self.end && moves > max_len => cut = true,
+                Some(next) if next == self.end => {
+                    path.push(next);
+                    found(&path);
+                    path.pop();
+                    count += 1;
+                    if count == max {
+                        return (count, true);
+                    }
+                }
+                // Past here the end is at least one more move away
+                Some(next) if !on_path[next.1][next.0] && moves >= max_len => cut = true,
+                Some(next) if !on_path[next.1][next.0] => {
+                    on_path[next.1][next.0] = true;
+                    path.push(next);
+                    stack.push(self.moves(next));
+                }
+                Some(_) => {}
+                None => {
+                    if let Some((x, y)) = path.pop() {
+                        on_path[y][x] = false;
+                    }
+                    stack.pop();
+                }
+            }
+        }
+        (count, cut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Maze;
+    use alloc::vec;
+
+    fn perfect(seed: u64) -> Maze {
+        let mut maze = Maze::new(10, 8).unwrap();
+        maze.generate_with_seed(seed);
+        maze
+    }
+
+    #[test]
+    fn perfect_maze_has_one_solution() {
+        for seed in 0..10 {
+            assert_eq!(perfect(seed).count_solutions(10, usize::MAX), (1, false));
+        }
+    }
+
+    #[test]
+    fn loops_add_solutions() {
+        for seed in 0..10 {
+            let mut maze = perfect(seed);
+            maze.add_loops(0.3, None);
+            let (count, _) = maze.count_solutions(50, usize::MAX);
+            assert!(count > 1, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn count_solutions_stops_at_max_len() {
+        let maze = perfect(4);
+        let moves = maze.solve().unwrap().len() - 1;
+        assert_eq!(maze.count_solutions(10, moves), (1, false));
+        assert_eq!(maze.count_solutions(10, moves - 1), (0, true));
+    }
+
+    #[test]
+    fn perfect_maze_has_one_path() {
+        for seed in 0..10 {
+            let maze = perfect(seed);
+            assert_eq!(maze.solve_all_paths(10), vec![maze.solve().unwrap()]);
+        }
+    }
+
+    #[test]
+    fn all_paths_are_sorted_by_length() {
+        let mut maze = perfect(6);
+        maze.add_loops(0.3, None);
+        let paths = maze.solve_all_paths(20);
+        assert!(paths.len() > 1);
+        assert!(paths.windows(2).all(|w| w[0].len() <= w[1].len()));
+    }
+
+    #[test]
+    fn river_factor_compares_solution_to_longest_path() {
+        for seed in 0..5 {
+            let mut maze = perfect(seed);
+            let stats = maze.statistics();
+            let ratio = stats.solution_length as f64 / stats.longest_path_length as f64;
+            assert_eq!(stats.river_factor, ratio);
+            assert!(stats.river_factor > 0.0 && stats.river_factor <= 1.0);
+
+            // The longest path's own endpoints make it the solution
+            let longest = maze.longest_path();
+            (maze.start, maze.end) = (longest[0], longest[longest.len() - 1]);
+            assert_eq!(maze.statistics().river_factor, 1.0);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+// src/animate.rs
+
+use crate::{DrawOptions, Maze};
+use image::{
+    Delay, Frame, ImageResult, Rgb, RgbImage,
+    codecs::gif::{GifEncoder, Repeat},
+};
+use std::{fs::File, io::BufWriter, path::Path};
+
+/// Cells the search has already expanded
+const VISITED_COLOR: Rgb<u8> = Rgb([200, 220, 255]);
+/// Cells at the distance currently being expanded
+const FRONTIER_COLOR: Rgb<u8> = Rgb([90, 140, 255]);
+
+impl Maze {
+    /// Frames of a breadth-first search spreading out from `self.start`:
+    /// one frame per distance from the start, with the newest layer in a
+    /// darker blue, until the layer holding `self.end` is reached. A last
+    /// frame adds the solution and the start/end markers.
+    pub fn draw_solve_animation(&self, opts: &DrawOptions) -> Vec<RgbImage> {
+        let mut frames = Vec::new();
+        self.for_each_solve_frame(opts, |img| frames.push(img.clone()));
+        frames
+    }
+
+    /// Encode `draw_solve_animation` as a looping GIF, showing each frame
+    /// for `frame_delay_ms`. Frames are encoded as they are drawn rather
+    /// than collected first.
+    pub fn save_solve_gif(
+        &self,
+        path: &Path,
+        opts: &DrawOptions,
+        frame_delay_ms: u32,
+    ) -> ImageResult<()> {
+        let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+        encoder.set_repeat(Repeat::Infinite)?;
+        let delay = Delay::from_numer_denom_ms(frame_delay_ms, 1);
+        let mut result = Ok(());
+        self.for_each_solve_frame(opts, |img| {
+            if result.is_ok() {
+                let rgba = image::DynamicImage::ImageRgb8(img.clone()).into_rgba8();
+                result = encoder.encode_frame(Frame::from_parts(rgba, 0, 0, delay));
+            }
+        });
+        result
+    }
+
+    /// Draw the search frames one at a time into a single canvas, handing
+    /// each to `emit`
+    fn for_each_solve_frame(&self, opts: &DrawOptions, mut emit: impl FnMut(&RgbImage)) {
+        let mut img = self.draw_no_solution(opts);
+        let dist = self.distances_from(self.start);
+        let last = match dist.get(self.end.1).and_then(|row| row.get(self.end.0)) {
+            Some(&d) if d != usize::MAX => d,
+            _ => dist
+                .iter()
+                .flatten()
+                .filter(|&&d| d != usize::MAX)
+                .max()
+                .copied()
+                .unwrap_or(0),
+        };
+
+        let mut layers = vec![Vec::new(); last + 1];
+        for (y, row) in dist.iter().enumerate() {
+            for (x, &d) in row.iter().enumerate() {
+                if d <= last {
+                    layers[d].push((x, y));
+                }
+            }
+        }
+
+        let mut previous: &[(usize, usize)] = &[];
+        for layer in &layers {
+            for &cell in previous {
+                self.fill_cell(&mut img, cell, opts, VISITED_COLOR);
+            }
+            for &cell in layer {
+                self.fill_cell(&mut img, cell, opts, FRONTIER_COLOR);
+            }
+            emit(&opts.pad(img.clone()));
+            previous = layer;
+        }
+
+        for &cell in previous {
+            self.fill_cell(&mut img, cell, opts, VISITED_COLOR);
+        }
+        let solution = self.drawn_solution(opts);
+        self.draw_solution(&mut img, &solution, opts);
+        self.draw_endpoints(&mut img, opts.cell_size, opts);
+        emit(&opts.pad(img));
+    }
+
+    /// Paint the inside of a cell, leaving the wall pixels along its west
+    /// and north edges (and those of its neighbours) untouched
+    fn fill_cell(
+        &self,
+        img: &mut RgbImage,
+        (x, y): (usize, usize),
+        opts: &DrawOptions,
+        color: Rgb<u8>,
+    ) {
+        let (cs, wt) = (opts.cell_size as u32, opts.wall_thickness as u32);
+        let (x0, y0) = (x as u32 * cs + wt, y as u32 * cs + wt);
+        for py in y0..(y as u32 + 1) * cs {
+            for px in x0..(x as u32 + 1) * cs {
+                img.put_pixel(px, py, color);
+            }
+        }
+    }
+}
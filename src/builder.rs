@@ -0,0 +1,283 @@
+// src/builder.rs
+
+use crate::{Algorithm, BinaryTreeBias, Maze, MazeError};
+use alloc::vec::Vec;
+
+/// Step-by-step configuration of a generated maze, finished by `build`.
+///
+/// `mask`, `toroidal`, `rooms` and an `algorithm` other than
+/// `Algorithm::Dfs` each pick how the maze is carved (the first three with
+/// their own backtracker), so `build` fails with
+/// `MazeError::ConflictingOptions` when more than one is set.
+/// `loop_fraction` applies to all of them.
+#[derive(Debug, Clone)]
+pub struct MazeBuilder {
+    width: Option<usize>,
+    height: Option<usize>,
+    algorithm: Algorithm,
+    seed: Option<u64>,
+    loop_fraction: f64,
+    mask: Option<Vec<Vec<bool>>>,
+    sidewinder_bias: f64,
+    binary_tree_bias: BinaryTreeBias,
+    rooms: usize,
+    room_min_size: (usize, usize),
+    room_max_size: (usize, usize),
+    toroidal: bool,
+    threads: usize,
+    min_difficulty: Option<(f64, usize)>,
+}
+
+impl Default for MazeBuilder {
+    fn default() -> Self {
+        MazeBuilder {
+            width: None,
+            height: None,
+            algorithm: Algorithm::default(),
+            seed: None,
+            loop_fraction: 0.0,
+            mask: None,
+            sidewinder_bias: 0.5,
+            binary_tree_bias: BinaryTreeBias::NorthEast,
+            rooms: 0,
+            room_min_size: (3, 3),
+            room_max_size: (6, 6),
+            toroidal: false,
+            threads: 1,
+            min_difficulty: None,
+        }
+    }
+}
+
+impl MazeBuilder {
+    /// A 50x50 depth-first maze with a random seed until told otherwise
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Width in cells [default: the mask's width, else 50]
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Height in cells [default: the mask's height, else 50]
+    pub fn height(mut self, height: usize) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Generation algorithm [default: `Algorithm::Dfs`]; anything else
+    /// conflicts with `mask`, `toroidal` and `rooms`
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Seed for a reproducible layout [default: random]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Fraction of the remaining interior walls to knock out afterwards
+    /// (see `Maze::add_loops`) [default: 0]
+    pub fn loop_fraction(mut self, fraction: f64) -> Self {
+        self.loop_fraction = fraction;
+        self
+    }
+
+    /// Carve only inside this mask (see `Maze::generate_masked`); conflicts
+    /// with `toroidal`, `rooms` and a non-default `algorithm`
+    pub fn mask(mut self, mask: Vec<Vec<bool>>) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Probability of extending a Sidewinder run east [default: 0.5]
+    pub fn sidewinder_bias(mut self, bias: f64) -> Self {
+        self.sidewinder_bias = bias;
+        self
+    }
+
+    /// Carving direction for `Algorithm::BinaryTree` [default: north-east]
+    pub fn binary_tree_bias(mut self, bias: BinaryTreeBias) -> Self {
+        self.binary_tree_bias = bias;
+        self
+    }
+
+    /// Carve up to `count` open rooms instead (see `Maze::carve_rooms`);
+    /// conflicts with `mask`, `toroidal` and a non-default `algorithm`
+    pub fn rooms(
+        mut self,
+        count: usize,
+        min_size: (usize, usize),
+        max_size: (usize, usize),
+    ) -> Self {
+        self.rooms = count;
+        self.room_min_size = min_size;
+        self.room_max_size = max_size;
+        self
+    }
+
+    /// Wrap the edges around (see `Maze::generate_toroidal`); conflicts
+    /// with `mask`, `rooms` and a non-default `algorithm`
+    pub fn toroidal(mut self, toroidal: bool) -> Self {
+        self.toroidal = toroidal;
+        self
+    }
+
+    /// Carve `Algorithm::Dfs` mazes on this many threads when above 1 (see
+    /// `Maze::generate_parallel`; ignored without the `std` feature)
+    /// [default: 1]
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Keep carving, with the seed counting up by one each time, until the
+    /// maze's `Maze::difficulty_score` is at least `min`; after
+    /// `max_attempts` failures `build` returns
+    /// `MazeError::DifficultyNotAchievable`. The maze returned stores the
+    /// seed that produced it, so it can still be reproduced. [default: off]
+    pub fn min_difficulty(mut self, min: f64, max_attempts: usize) -> Self {
+        self.min_difficulty = Some((min, max_attempts));
+        self
+    }
+
+    /// Create and carve the maze. Fails with `MazeError::ConflictingOptions`
+    /// when the options above say to carve it two different ways.
+    pub fn build(self) -> Result<Maze, MazeError> {
+        self.build_with_progress(|_, _| {})
+    }
+
+    /// Same as `build`, passing `progress` to the generators that report it
+    /// (`Algorithm::Dfs` and `Algorithm::Wilson`); see
+    /// `Maze::generate_with_progress`
+    pub fn build_with_progress(
+        self,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<Maze, MazeError> {
+        let carvers = [
+            ("mask", self.mask.is_some()),
+            ("toroidal", self.toroidal),
+            ("rooms", self.rooms > 0),
+            ("algorithm", self.algorithm != Algorithm::Dfs),
+        ];
+        let mut set = carvers.iter().filter(|(_, on)| *on).map(|(name, _)| *name);
+        if let (Some(first), Some(second)) = (set.next(), set.next()) {
+            return Err(MazeError::ConflictingOptions { first, second });
+        }
+        let Some((min, max_attempts)) = self.min_difficulty else {
+            return self.carve(self.seed, progress);
+        };
+        let mut seed = self.seed.unwrap_or_else(crate::random_seed);
+        for _ in 0..max_attempts {
+            let maze = self.carve(Some(seed), &mut progress)?;
+            if maze.difficulty_score() >= min {
+                return Ok(maze);
+            }
+            seed = seed.wrapping_add(1);
+        }
+        Err(MazeError::DifficultyNotAchievable)
+    }
+
+    /// One maze from the configured generator with `seed` (random if `None`)
+    fn carve(
+        &self,
+        seed: Option<u64>,
+        progress: impl FnMut(usize, usize),
+    ) -> Result<Maze, MazeError> {
+        let (mask_w, mask_h) = match &self.mask {
+            Some(mask) => (mask.first().map_or(0, Vec::len), mask.len()),
+            None => (50, 50),
+        };
+        let mut maze = Maze::new(self.width.unwrap_or(mask_w), self.height.unwrap_or(mask_h))?;
+        if let Some(seed) = seed {
+            maze.seed = seed;
+        }
+        if let Some(mask) = &self.mask {
+            maze.generate_masked(mask)?;
+        } else if self.toroidal {
+            maze.generate_toroidal();
+        } else if self.rooms > 0 {
+            maze.carve_rooms(self.rooms, self.room_min_size, self.room_max_size);
+        } else {
+            match self.algorithm {
+                #[cfg(feature = "std")]
+                Algorithm::Dfs if self.threads > 1 => maze.generate_parallel(self.threads),
+                Algorithm::Dfs => maze.generate_with_progress(progress),
+                Algorithm::Prim => maze.generate_prim(),
+                Algorithm::Kruskal => maze.generate_kruskal(),
+                Algorithm::Wilson => maze.generate_wilson_with_progress(progress),
+                Algorithm::AldousBroder => {
+                    maze.generate_aldous_broder();
+                }
+                Algorithm::Sidewinder => maze.generate_sidewinder(self.sidewinder_bias),
+                Algorithm::BinaryTree => maze.generate_binary_tree(self.binary_tree_bias),
+                Algorithm::HuntAndKill => maze.generate_hunt_and_kill(),
+                Algorithm::RecursiveDivision => maze.generate_recursive_division(),
+                Algorithm::Eller => maze.generate_eller(),
+            }
+        }
+        if self.loop_fraction > 0.0 {
+            maze.add_loops(self.loop_fraction, None);
+        }
+        Ok(maze)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MazeBuilder;
+    use crate::{Algorithm, MazeError};
+    use alloc::vec;
+
+    #[test]
+    fn conflicting_carvers_are_rejected() {
+        let err = MazeBuilder::new()
+            .width(8)
+            .height(8)
+            .algorithm(Algorithm::Kruskal)
+            .toroidal(true)
+            .build();
+        assert_eq!(
+            err,
+            Err(MazeError::ConflictingOptions {
+                first: "toroidal",
+                second: "algorithm"
+            })
+        );
+        let err = MazeBuilder::new()
+            .mask(vec![vec![true; 8]; 8])
+            .rooms(2, (2, 2), (3, 3))
+            .build();
+        assert_eq!(
+            err,
+            Err(MazeError::ConflictingOptions {
+                first: "mask",
+                second: "rooms"
+            })
+        );
+    }
+
+    #[test]
+    fn a_single_carver_builds() {
+        let toroidal = MazeBuilder::new()
+            .width(8)
+            .height(8)
+            .seed(3)
+            .algorithm(Algorithm::Dfs)
+            .toroidal(true)
+            .build()
+            .unwrap();
+        assert!(toroidal.toroidal && toroidal.is_perfect());
+        let kruskal = MazeBuilder::new()
+            .width(8)
+            .height(8)
+            .algorithm(Algorithm::Kruskal)
+            .build()
+            .unwrap();
+        assert!(kruskal.is_perfect());
+    }
+}
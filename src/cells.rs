@@ -0,0 +1,58 @@
+// src/cells.rs
+
+use crate::{Maze, WallSide};
+
+/// One cell as yielded by `Maze::cells`; each `open_*` is true when no wall
+/// stands on that side. On the grid edge that only happens in a toroidal
+/// maze, where the passage wraps around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellInfo {
+    pub x: usize,
+    pub y: usize,
+    pub open_north: bool,
+    pub open_south: bool,
+    pub open_east: bool,
+    pub open_west: bool,
+}
+
+/// A standing wall as yielded by `Maze::walls`: the `side` wall of cell
+/// `(x, y)`, with `x == width` or `y == height` for the east and south
+/// edges of the grid (see `WallSide`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WallInfo {
+    pub x: usize,
+    pub y: usize,
+    pub side: WallSide,
+}
+
+impl Maze {
+    /// Every cell row by row, top-left first, computed lazily from the wall
+    /// arrays
+    pub fn cells(&self) -> impl Iterator<Item = CellInfo> + '_ {
+        (0..self.height).flat_map(move |y| {
+            (0..self.width).map(move |x| CellInfo {
+                x,
+                y,
+                open_north: !self.hor_walls[y][x],
+                open_south: !self.hor_walls[y + 1][x],
+                open_east: !self.vert_walls[y][x + 1],
+                open_west: !self.vert_walls[y][x],
+            })
+        })
+    }
+
+    /// Every wall still standing, outer walls included, row by row with the
+    /// north wall of each position before its west wall
+    pub fn walls(&self) -> impl Iterator<Item = WallInfo> + '_ {
+        (0..=self.height).flat_map(move |y| {
+            (0..=self.width).flat_map(move |x| {
+                let north = x < self.width && self.hor_walls[y][x];
+                let west = y < self.height && self.vert_walls[y][x];
+                [(north, WallSide::North), (west, WallSide::West)]
+                    .into_iter()
+                    .filter(|&(standing, _)| standing)
+                    .map(move |(_, side)| WallInfo { x, y, side })
+            })
+        })
+    }
+}
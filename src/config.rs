@@ -0,0 +1,528 @@
+// src/config.rs
+
+use crate::{
+    Algorithm, BinaryTreeBias, DrawOptions, KrunkerTheme, MapOptions, MarkerStyle, Mask, Maze,
+    MazeBuilder, MazeError, TEXTURE_IDS, WeightZone,
+};
+use clap::{Parser, ValueEnum};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Colour preset for the PNG
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DrawTheme {
+    /// Black walls on white
+    Light,
+    /// White walls on black
+    Dark,
+    /// Pale walls on navy, yellow solution
+    Blueprint,
+}
+
+/// Lighting preset for the Krunker map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MapTheme {
+    /// Neutral grey daylight
+    Default,
+    /// Dim torch light, dark fog
+    Dungeon,
+    /// Bright, cold light, white fog
+    Snow,
+    /// Warm sunlight, sandy haze
+    Desert,
+}
+
+/// The options that describe a maze, its drawing and its Krunker map,
+/// shared by the binary's command line and `maze.toml`. Each field is a
+/// command-line option (`cell_size` is `--cell-size`) and a TOML key of
+/// the same name; both take their defaults from the `#[arg]` attributes,
+/// so `MazeConfig::default()` is the command line with no options. Keys
+/// only the binary uses, such as output paths, are ignored when reading a
+/// file.
+#[derive(Debug, Clone, PartialEq, clap::Args, Deserialize)]
+#[serde(default)]
+pub struct MazeConfig {
+    /// Maze width in cells
+    #[arg(short = 'W', long, default_value_t = 50)]
+    pub width: usize,
+    /// Maze height in cells
+    #[arg(short = 'H', long, default_value_t = 50)]
+    pub height: usize,
+    /// Pixel size of each cell
+    #[arg(short = 'S', long, default_value_t = 40)]
+    pub cell_size: usize,
+    /// Wall thickness in pixels
+    #[arg(short = 'T', long, default_value_t = 4)]
+    pub wall_thickness: usize,
+    /// Empty border around the images in pixels [default: half a cell]
+    #[arg(long)]
+    pub padding: Option<usize>,
+    /// Seed for reproducible generation (random if omitted)
+    #[arg(short, long)]
+    pub seed: Option<u64>,
+    /// Generation algorithm
+    #[arg(short = 'A', long, value_enum, default_value_t = Algorithm::Dfs)]
+    pub algorithm: Algorithm,
+    /// Sidewinder probability of extending a run east (0.0–1.0)
+    #[arg(long, default_value_t = 0.5)]
+    pub sidewinder_bias: f64,
+    /// Binary tree carving bias (north-east, north-west, south-east, south-west)
+    #[arg(long, default_value = "north-east")]
+    pub binary_tree_bias: BinaryTreeBias,
+    /// Leave the solution path out of the PNG, SVG, transparent PNG and GIF
+    #[arg(long)]
+    pub no_solution: bool,
+    /// Start cell column [default: 0]
+    #[arg(long)]
+    pub start_x: Option<usize>,
+    /// Start cell row [default: 0]
+    #[arg(long)]
+    pub start_y: Option<usize>,
+    /// End cell column [default: width - 1]
+    #[arg(long)]
+    pub end_x: Option<usize>,
+    /// End cell row [default: height - 1]
+    #[arg(long)]
+    pub end_y: Option<usize>,
+    /// Add arrowheads to the drawn solution at each turn and at the exit
+    #[arg(long)]
+    pub arrows: bool,
+    /// Shade the drawn solution from green at the start to red at the end
+    #[arg(long)]
+    pub solution_gradient: bool,
+    /// Colour theme of the PNG
+    #[arg(long, value_enum, default_value_t = DrawTheme::Light)]
+    pub theme: DrawTheme,
+    /// Shape of the start and end markers [default: circle for the start,
+    /// square for the end]
+    #[arg(long, value_enum)]
+    pub marker_style: Option<MarkerStyle>,
+    /// Also draw the maze's longest path (in blue) in the PNG and SVG
+    #[arg(long)]
+    pub show_longest_path: bool,
+    /// Mark dead ends (in orange) in the PNG and SVG
+    #[arg(long)]
+    pub mark_dead_ends: bool,
+    /// Mark articulation points, the cells every route between two parts
+    /// of the maze must pass (in yellow), in the PNG and SVG
+    #[arg(long)]
+    pub mark_articulation_points: bool,
+    /// Print each cell's coordinates in the PNG, for debugging; needs a
+    /// --cell-size of at least 20
+    #[arg(long)]
+    pub show_coordinates: bool,
+    /// Regenerate with the next seed until the difficulty score reaches this
+    /// (0.0–1.0)
+    #[arg(long)]
+    pub min_difficulty: Option<f64>,
+    /// Seeds --min-difficulty tries before giving up
+    #[arg(long, default_value_t = 100)]
+    pub difficulty_attempts: usize,
+    /// Fraction of the remaining interior walls to remove afterwards, adding loops (0.0–1.0)
+    #[arg(long, default_value_t = 0.0)]
+    pub loop_fraction: f64,
+    /// Number of open rooms to carve (generates corridors with backtracking around them)
+    #[arg(long, default_value_t = 0)]
+    pub rooms: usize,
+    /// Number of portal pairs joining random cells; the solution may
+    /// teleport through them and the map marks each end with a trigger
+    #[arg(long, default_value_t = 0)]
+    pub portals: usize,
+    /// JSON weight map for the solver: a list of {"x", "y", "weight"}
+    /// zones, optionally with "width" and "height" in cells
+    #[arg(long, value_name = "FILE")]
+    pub weights: Option<PathBuf>,
+    /// Smallest room side in cells
+    #[arg(long, default_value_t = 3)]
+    pub room_min: usize,
+    /// Largest room side in cells
+    #[arg(long, default_value_t = 6)]
+    pub room_max: usize,
+    /// Carve only inside the dark pixels of this image, one pixel per cell;
+    /// its size replaces --width/--height
+    #[arg(long)]
+    pub mask_image: Option<PathBuf>,
+    /// Luma below which a --mask-image pixel counts as inside the maze
+    #[arg(long, default_value_t = 128)]
+    pub mask_threshold: u8,
+    /// Join the left/right and top/bottom edges (recursive backtracker)
+    #[arg(long)]
+    pub toroidal: bool,
+    /// Carve depth-first mazes on this many threads, one horizontal stripe each
+    #[arg(long, default_value_t = 1)]
+    pub threads: usize,
+    /// Height of the map walls
+    #[arg(long, default_value_t = 20)]
+    pub wall_height: usize,
+    /// Also cover the map with a ceiling slab at this height
+    #[arg(long)]
+    pub ceiling_height: Option<usize>,
+    /// Cover the map with a ceiling slab just above the walls
+    #[arg(long)]
+    pub ceiling: bool,
+    /// Leave out the walls around the edge of the map
+    #[arg(long)]
+    pub no_outer_walls: bool,
+    /// Open the map's entrance (north of the top-left cell) and exit (south
+    /// of the bottom-right cell) and frame them with doors
+    #[arg(long)]
+    pub doors: bool,
+    /// Height of the --doors openings
+    #[arg(long, default_value_t = 16)]
+    pub door_height: usize,
+    /// Stand a pillar on every 5th cell of the solution in the map
+    #[arg(long)]
+    pub mark_solution_path: bool,
+    /// Krunker texture ID of the --mark-solution-path pillars
+    #[arg(long, default_value_t = 1, value_parser = texture_id)]
+    pub waypoint_texture: u32,
+    /// Spread this many map spawns over the dead ends instead of using the
+    /// start and end cells
+    #[arg(long, default_value_t = 0)]
+    pub spawn_count: usize,
+    /// Krunker texture ID of the map floor
+    #[arg(long, default_value_t = 0, value_parser = texture_id)]
+    pub floor_texture: u32,
+    /// Krunker texture ID of the map walls
+    #[arg(long, default_value_t = 0, value_parser = texture_id)]
+    pub wall_texture: u32,
+    /// Krunker texture ID of the --ceiling-height slab
+    #[arg(long, value_parser = texture_id)]
+    pub ceiling_texture: Option<u32>,
+    /// Lighting, sky and fog preset of the map
+    #[arg(long, value_enum, default_value_t = MapTheme::Default)]
+    pub map_theme: MapTheme,
+}
+
+/// `MazeConfig` as a whole command line, to read its defaults off clap
+#[derive(Parser)]
+struct Defaults {
+    #[command(flatten)]
+    config: MazeConfig,
+}
+
+impl Default for MazeConfig {
+    fn default() -> Self {
+        Defaults::parse_from(["krunker-maze-generator"]).config
+    }
+}
+
+/// Parse a Krunker texture ID, rejecting values outside `TEXTURE_IDS`
+fn texture_id(s: &str) -> Result<u32, String> {
+    let id: u32 = s.parse().map_err(|e| format!("{e}"))?;
+    if TEXTURE_IDS.contains(&id) {
+        Ok(id)
+    } else {
+        Err(format!(
+            "texture IDs range from {} to {}",
+            TEXTURE_IDS.start(),
+            TEXTURE_IDS.end()
+        ))
+    }
+}
+
+impl MazeConfig {
+    /// Read a `maze.toml` file; missing keys keep their defaults. Keys
+    /// that are not `MazeConfig` fields are skipped without complaint, so
+    /// that the binary's output options can share the file; a misspelt
+    /// key is skipped too, where `--config` would reject it. Fails with
+    /// `MazeError::InvalidConfig` when the file cannot be read or parsed
+    /// or names an unknown texture.
+    pub fn load(path: &Path) -> Result<MazeConfig, MazeError> {
+        let invalid =
+            |message: String| MazeError::InvalidConfig(format!("{}: {message}", path.display()));
+        let text = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        let config: MazeConfig =
+            toml::from_str(&text).map_err(|e| invalid(e.message().to_owned()))?;
+        let textures = [
+            config.floor_texture,
+            config.wall_texture,
+            config.waypoint_texture,
+        ];
+        if let Some(id) = textures
+            .into_iter()
+            .chain(config.ceiling_texture)
+            .find(|id| !TEXTURE_IDS.contains(id))
+        {
+            return Err(invalid(format!(
+                "texture ID {id} is outside {}..={}",
+                TEXTURE_IDS.start(),
+                TEXTURE_IDS.end()
+            )));
+        }
+        Ok(config)
+    }
+
+    /// The generator these options configure, without the seed, and the
+    /// size of the mazes it builds. Fails with `MazeError::ImageLoad` when
+    /// the `mask_image` cannot be read.
+    pub fn builder(&self) -> Result<(MazeBuilder, (usize, usize)), MazeError> {
+        let mut builder = MazeBuilder::new()
+            .algorithm(self.algorithm)
+            .loop_fraction(self.loop_fraction)
+            .sidewinder_bias(self.sidewinder_bias)
+            .binary_tree_bias(self.binary_tree_bias)
+            .toroidal(self.toroidal)
+            .threads(self.threads);
+        if let Some(min) = self.min_difficulty {
+            builder = builder.min_difficulty(min, self.difficulty_attempts);
+        }
+        let size = match &self.mask_image {
+            Some(path) => {
+                let mask = Mask::from_image(path, self.mask_threshold)
+                    .map_err(|e| MazeError::ImageLoad(format!("{}: {e}", path.display())))?;
+                let size = (mask.first().map_or(0, Vec::len), mask.len());
+                builder = builder.mask(mask);
+                size
+            }
+            None => {
+                builder = builder.width(self.width).height(self.height);
+                (self.width, self.height)
+            }
+        };
+        if self.rooms > 0 {
+            builder = builder.rooms(
+                self.rooms,
+                (self.room_min, self.room_min),
+                (self.room_max, self.room_max),
+            );
+        }
+        Ok((builder, size))
+    }
+
+    /// Move `maze`'s start and end to the configured cells, then add the
+    /// portals and solver weights. Fails with `MazeError::OutOfBounds` when
+    /// the start or end lies outside the grid, `MazeError::Unreachable`
+    /// when no path joins them, `MazeError::InvalidConfig` when the
+    /// `weights` file cannot be read and as `Maze::apply_weight_zones`
+    /// does.
+    pub fn apply(&self, maze: &mut Maze) -> Result<(), MazeError> {
+        let start = (
+            self.start_x.unwrap_or(maze.start.0),
+            self.start_y.unwrap_or(maze.start.1),
+        );
+        let end = (
+            self.end_x.unwrap_or(maze.end.0),
+            self.end_y.unwrap_or(maze.end.1),
+        );
+        for (x, y) in [start, end] {
+            if !maze.in_bounds((x, y)) {
+                return Err(MazeError::OutOfBounds { x, y });
+            }
+        }
+        if maze.solve_between(start, end).is_none() {
+            return Err(MazeError::Unreachable {
+                from: start,
+                to: end,
+            });
+        }
+        maze.start = start;
+        maze.end = end;
+        maze.add_portal_pairs(self.portals, None);
+        if let Some(path) = &self.weights {
+            let invalid = |message: String| {
+                MazeError::InvalidConfig(format!("{}: {message}", path.display()))
+            };
+            let text = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+            let zones: Vec<WeightZone> =
+                serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+            maze.apply_weight_zones(&zones)?;
+        }
+        Ok(())
+    }
+
+    /// Generate the configured maze: `builder` with the seed, then `apply`
+    pub fn build(&self) -> Result<Maze, MazeError> {
+        let (mut builder, _) = self.builder()?;
+        if let Some(seed) = self.seed {
+            builder = builder.seed(seed);
+        }
+        let mut maze = builder.build()?;
+        self.apply(&mut maze)?;
+        Ok(maze)
+    }
+
+    /// PNG drawing options: the `theme` preset with the sizes and overlays
+    /// set here
+    pub fn draw_options(&self) -> DrawOptions {
+        let base = match self.theme {
+            DrawTheme::Light => DrawOptions::default(),
+            DrawTheme::Dark => DrawOptions::dark_mode(),
+            DrawTheme::Blueprint => DrawOptions::blueprint(),
+        };
+        DrawOptions {
+            cell_size: self.cell_size,
+            wall_thickness: self.wall_thickness,
+            show_solution: !self.no_solution,
+            padding: self.padding.unwrap_or(self.cell_size / 2),
+            show_arrows: self.arrows,
+            solution_gradient: self.solution_gradient,
+            marker_style: self.marker_style,
+            show_coordinates: self.show_coordinates,
+            show_longest_path: self.show_longest_path,
+            mark_dead_ends: self.mark_dead_ends,
+            mark_articulation_points: self.mark_articulation_points,
+            ..base
+        }
+    }
+
+    /// Krunker map geometry options
+    pub fn map_options(&self) -> MapOptions {
+        MapOptions {
+            cell_size: self.cell_size,
+            wall_thickness: self.wall_thickness,
+            wall_height: self.wall_height,
+            ceiling_height: self.ceiling_height,
+            add_ceiling: self.ceiling,
+            add_outer_walls: !self.no_outer_walls,
+            spawn_count: self.spawn_count,
+            floor_texture: self.floor_texture,
+            wall_texture: self.wall_texture,
+            ceiling_texture: self.ceiling_texture,
+            add_doors: self.doors,
+            door_height: self.door_height,
+            mark_solution_path: self.mark_solution_path,
+            waypoint_texture: self.waypoint_texture,
+            ..MapOptions::default()
+        }
+    }
+
+    /// Lighting, sky and fog of the `map_theme` preset
+    pub fn krunker_theme(&self) -> KrunkerTheme {
+        match self.map_theme {
+            MapTheme::Default => KrunkerTheme::default(),
+            MapTheme::Dungeon => KrunkerTheme::dungeon(),
+            MapTheme::Snow => KrunkerTheme::snow(),
+            MapTheme::Desert => KrunkerTheme::desert(),
+        }
+    }
+}
+
+impl Maze {
+    /// Generate the maze a `maze.toml` file describes, with the drawing and
+    /// Krunker map options it sets; see `MazeConfig`, which reads the file.
+    /// The file uses the binary's option names with underscores
+    /// (`cell_size = 24`, `algorithm = "prim"`), so the same file drives
+    /// `--config`, except that keys this function does not know, typos
+    /// included, are ignored rather than rejected as `--config` does.
+    /// Fails as `MazeConfig::load` and `MazeConfig::build` do.
+    pub fn from_config(path: &Path) -> Result<(Maze, DrawOptions, MapOptions), MazeError> {
+        let config = MazeConfig::load(path)?;
+        let maze = config.build()?;
+        Ok((maze, config.draw_options(), config.map_options()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Defaults, DrawTheme, MazeConfig};
+    use crate::{Algorithm, MazeError};
+    use clap::Parser;
+
+    fn from_cli(options: &[&str]) -> MazeConfig {
+        let cli = ["krunker-maze-generator"].iter().chain(options);
+        Defaults::try_parse_from(cli).unwrap().config
+    }
+
+    #[test]
+    fn toml_keys_and_flags_agree() {
+        let toml: MazeConfig = toml::from_str(
+            "width = 12\ncell_size = 24\nalgorithm = \"prim\"\ntheme = \"dark\"\n\
+             portals = 2\nthreads = 3\nmask_threshold = 90\nno_solution = true\n\
+             binary_tree_bias = \"south-west\"\nceiling_texture = 5\n",
+        )
+        .unwrap();
+        let cli = from_cli(&[
+            "-W",
+            "12",
+            "--cell-size",
+            "24",
+            "--algorithm",
+            "prim",
+            "--theme",
+            "dark",
+            "--portals",
+            "2",
+            "--threads=3",
+            "--mask-threshold",
+            "90",
+            "--no-solution",
+            "--binary-tree-bias",
+            "south-west",
+            "--ceiling-texture",
+            "5",
+        ]);
+        assert_eq!(toml, cli);
+        assert_eq!(
+            (cli.algorithm, cli.theme),
+            (Algorithm::Prim, DrawTheme::Dark)
+        );
+        assert_eq!(cli.portals, 2);
+    }
+
+    #[test]
+    fn unknown_keys_are_ignored() {
+        let config: MazeConfig = toml::from_str("widht = 12\nsvg = \"maze.svg\"\n").unwrap();
+        assert_eq!(config, MazeConfig::default());
+    }
+
+    #[test]
+    fn defaults_come_from_the_command_line() {
+        let empty: MazeConfig = toml::from_str("").unwrap();
+        assert_eq!(empty, MazeConfig::default());
+        assert_eq!(empty, from_cli(&[]));
+        assert_eq!((empty.width, empty.cell_size), (50, 40));
+        assert_eq!(empty.draw_options().padding, 20);
+    }
+
+    #[test]
+    fn build_applies_portals_and_endpoints() {
+        let config = MazeConfig {
+            width: 8,
+            height: 6,
+            seed: Some(3),
+            portals: 2,
+            end_x: Some(4),
+            ..MazeConfig::default()
+        };
+        let maze = config.build().unwrap();
+        assert_eq!(maze.portals.len(), 2);
+        assert_eq!(maze.end, (4, 5));
+
+        let outside = MazeConfig {
+            start_y: Some(6),
+            ..config
+        };
+        assert_eq!(
+            outside.build().unwrap_err(),
+            MazeError::OutOfBounds { x: 0, y: 6 }
+        );
+    }
+
+    #[test]
+    fn apply_sets_endpoints_and_weights_of_every_seed() {
+        let weights = std::env::temp_dir().join("krunker-maze-generator-apply-weights.json");
+        std::fs::write(&weights, r#"[{"x": 1, "y": 2, "weight": 7, "width": 2}]"#).unwrap();
+        let config = MazeConfig {
+            width: 8,
+            height: 6,
+            start_x: Some(5),
+            start_y: Some(5),
+            end_x: Some(2),
+            weights: Some(weights.clone()),
+            ..MazeConfig::default()
+        };
+        // As `--batch` does: one builder, a seed per maze, then `apply`
+        let (builder, _) = config.builder().unwrap();
+        for seed in 0..4 {
+            let mut maze = builder.clone().seed(seed).build().unwrap();
+            config.apply(&mut maze).unwrap();
+            assert_eq!((maze.start, maze.end), ((5, 5), (2, 5)));
+            assert_eq!(maze.cell_weight(2, 2), 7);
+            assert_eq!(maze.cell_weight(3, 2), 1);
+        }
+        std::fs::remove_file(weights).unwrap();
+    }
+}
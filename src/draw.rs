@@ -0,0 +1,667 @@
+// src/draw.rs
+
+use crate::Maze;
+use clap::ValueEnum;
+use image::{ImageResult, Rgb, RgbImage, Rgba, RgbaImage, codecs::png::PngEncoder, imageops};
+use serde::Deserialize;
+use std::{collections::BTreeSet, io::Cursor};
+
+/// Linear blend between two colors, `t` in 0.0–1.0
+pub(crate) fn lerp_color(a: Rgb<u8>, b: Rgb<u8>, t: f64) -> Rgb<u8> {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |i: usize| (a[i] as f64 + (b[i] as f64 - a[i] as f64) * t).round() as u8;
+    Rgb([mix(0), mix(1), mix(2)])
+}
+
+/// Blend between two colours through HSV: hue turns the short way round
+/// the colour wheel while saturation and value change linearly
+pub(crate) fn lerp_hsv(a: Rgb<u8>, b: Rgb<u8>, t: f64) -> Rgb<u8> {
+    let t = t.clamp(0.0, 1.0);
+    let ([h1, s1, v1], [h2, s2, v2]) = (to_hsv(a), to_hsv(b));
+    let turn = (h2 - h1 + 540.0) % 360.0 - 180.0;
+    from_hsv([
+        (h1 + turn * t + 360.0) % 360.0,
+        s1 + (s2 - s1) * t,
+        v1 + (v2 - v1) * t,
+    ])
+}
+
+/// Hue in degrees, saturation and value in 0.0–1.0
+fn to_hsv(c: Rgb<u8>) -> [f64; 3] {
+    let [r, g, b] = c.0.map(|v| v as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let sat = if max == 0.0 { 0.0 } else { delta / max };
+    [hue, sat, max]
+}
+
+fn from_hsv([h, s, v]: [f64; 3]) -> Rgb<u8> {
+    let c = v * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    Rgb([r, g, b].map(|ch| ((ch + m) * 255.0).round() as u8))
+}
+
+/// Shape of the start/end markers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MarkerStyle {
+    Circle,
+    Square,
+    Diamond,
+}
+
+/// Everything `Maze::draw` needs to know; the default is black walls on
+/// white with a red solution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrawOptions {
+    pub background: Rgb<u8>,
+    pub wall: Rgb<u8>,
+    pub solution: Rgb<u8>,
+    /// Paint the start-to-end solution; turn off for puzzles to hand out,
+    /// which also skips solving the maze
+    pub show_solution: bool,
+    /// Opacity of the solution and its arrowheads in `draw_rgba`
+    pub solution_alpha: u8,
+    /// Pixel size of each cell
+    pub cell_size: usize,
+    /// Wall thickness in pixels
+    pub wall_thickness: usize,
+    /// Overlay arrowheads on the solution at each turn and at the exit
+    pub show_arrows: bool,
+    /// Fill of the marker on `start`
+    pub start_color: Rgb<u8>,
+    /// Fill of the marker on `end`
+    pub end_color: Rgb<u8>,
+    /// Shape of both markers; `None` draws a circle on the start and a
+    /// square on the end
+    pub marker_style: Option<MarkerStyle>,
+    /// Empty border in pixels around the whole drawing, in `background`
+    /// (transparent in `draw_rgba`); `cell_size / 2` looks good when the
+    /// image is embedded in a page
+    pub padding: usize,
+    /// Colour the solution from green at the start to red at the end,
+    /// blended in HSV, instead of in `solution`
+    pub solution_gradient: bool,
+    /// Fill of cells with weight 0 and of the costliest cells when the
+    /// maze has cell weights. Cells of weight 1 keep `background` and
+    /// those in between blend from it to the second colour.
+    pub weight_gradient: (Rgb<u8>, Rgb<u8>),
+    /// Print each cell's `x,y` in a 5x7 pixel font, in the wall colour,
+    /// for debugging; cells smaller than `COORDINATE_MIN_CELL` are left
+    /// blank since the text would not fit
+    pub show_coordinates: bool,
+    /// Colour the walls along the split lines of `Maze::generate_fractal`
+    /// by subdivision level, cycling red, blue, green, orange and purple;
+    /// walls inside the innermost regions keep `wall`
+    pub fractal_coloring: bool,
+    /// Paint `Maze::longest_path` in blue underneath the solution
+    pub show_longest_path: bool,
+    /// Mark every dead end with a small orange square
+    pub mark_dead_ends: bool,
+    /// Mark every `Maze::articulation_points` cell with a small yellow
+    /// square
+    pub mark_articulation_points: bool,
+}
+
+impl Default for DrawOptions {
+    fn default() -> Self {
+        DrawOptions {
+            background: Rgb([255, 255, 255]),
+            wall: Rgb([0, 0, 0]),
+            solution: Rgb([255, 0, 0]),
+            show_solution: true,
+            solution_alpha: 255,
+            cell_size: 40,
+            wall_thickness: 4,
+            show_arrows: false,
+            start_color: Rgb([0, 170, 0]),
+            end_color: Rgb([200, 0, 0]),
+            marker_style: None,
+            padding: 0,
+            solution_gradient: false,
+            weight_gradient: (Rgb([170, 230, 170]), Rgb([150, 100, 50])),
+            show_coordinates: false,
+            fractal_coloring: false,
+            show_longest_path: false,
+            mark_dead_ends: false,
+            mark_articulation_points: false,
+        }
+    }
+}
+
+impl DrawOptions {
+    /// White walls on black
+    pub fn dark_mode() -> Self {
+        DrawOptions {
+            background: Rgb([0, 0, 0]),
+            wall: Rgb([255, 255, 255]),
+            ..Self::default()
+        }
+    }
+
+    /// Pale walls on navy with a yellow solution
+    pub fn blueprint() -> Self {
+        DrawOptions {
+            background: Rgb([16, 52, 110]),
+            wall: Rgb([230, 240, 255]),
+            solution: Rgb([255, 210, 0]),
+            ..Self::default()
+        }
+    }
+
+    /// Colour of step `step` (0-based) of a solution `steps` moves long
+    pub(crate) fn solution_color(&self, step: usize, steps: usize) -> Rgb<u8> {
+        if !self.solution_gradient {
+            return self.solution;
+        }
+        let t = step as f64 / steps.saturating_sub(1).max(1) as f64;
+        lerp_hsv(GRADIENT_START, GRADIENT_END, t)
+    }
+
+    /// `img` centred on a canvas `padding` pixels larger on every side,
+    /// filled with `background`. The `draw*` methods that return a finished
+    /// image apply this themselves; call it after painting extra layers on
+    /// `draw_no_solution`.
+    pub fn pad(&self, img: RgbImage) -> RgbImage {
+        if self.padding == 0 {
+            return img;
+        }
+        let p = self.padding as u32;
+        let mut out =
+            RgbImage::from_pixel(img.width() + 2 * p, img.height() + 2 * p, self.background);
+        imageops::replace(&mut out, &img, p.into(), p.into());
+        out
+    }
+}
+
+/// Heat map gradient ends, for the start cell and the farthest cell
+const HEAT_NEAR: Rgb<u8> = Rgb([0, 0, 255]);
+const HEAT_FAR: Rgb<u8> = Rgb([255, 0, 0]);
+
+/// Ends of the `solution_gradient` colouring
+const GRADIENT_START: Rgb<u8> = Rgb([0, 255, 0]);
+const GRADIENT_END: Rgb<u8> = Rgb([255, 0, 0]);
+
+/// Colour of the solution arrowheads, dark enough to read on the path
+pub(crate) const ARROW_COLOR: Rgb<u8> = Rgb([128, 0, 0]);
+
+/// Colour of the one-way passage arrowheads
+pub(crate) const ONE_WAY_COLOR: Rgb<u8> = Rgb([0, 0, 192]);
+
+/// Colour of `DrawOptions::show_longest_path`
+pub(crate) const LONGEST_PATH_COLOR: Rgb<u8> = Rgb([0, 0, 255]);
+
+/// Colour of the `DrawOptions::mark_dead_ends` markers
+pub(crate) const DEAD_END_COLOR: Rgb<u8> = Rgb([255, 165, 0]);
+
+/// Colour of the `DrawOptions::mark_articulation_points` markers
+pub(crate) const ARTICULATION_POINT_COLOR: Rgb<u8> = Rgb([255, 255, 0]);
+
+/// Wall colours of the `generate_fractal` split lines, by level
+pub(crate) const FRACTAL_COLORS: [Rgb<u8>; 5] = [
+    Rgb([200, 0, 0]),
+    Rgb([0, 70, 200]),
+    Rgb([0, 150, 0]),
+    Rgb([230, 130, 0]),
+    Rgb([140, 0, 170]),
+];
+
+/// Fill a triangular arrowhead `size` pixels long centred on (`cx`, `cy`),
+/// pointing along the unit vector (`dx`, `dy`)
+fn paint_arrow(
+    img: &mut RgbImage,
+    (cx, cy): (f64, f64),
+    (dx, dy): (f64, f64),
+    size: f64,
+    color: Rgb<u8>,
+) {
+    let r = size / 2.0;
+    for py in (cy - r).floor() as i64..=(cy + r).ceil() as i64 {
+        for px in (cx - r).floor() as i64..=(cx + r).ceil() as i64 {
+            if px < 0 || py < 0 || px as u32 >= img.width() || py as u32 >= img.height() {
+                continue;
+            }
+            let (ox, oy) = (px as f64 + 0.5 - cx, py as f64 + 0.5 - cy);
+            // Distance along the arrow and to either side of its axis; the
+            // triangle narrows from half a size at the base to 0 at the tip
+            let along = ox * dx + oy * dy;
+            let across = (ox * dy - oy * dx).abs();
+            if along.abs() <= r && across <= (r - along) / 2.0 {
+                img.put_pixel(px as u32, py as u32, color);
+            }
+        }
+    }
+}
+
+/// Smallest `cell_size` that `draw_coordinates` prints into
+pub const COORDINATE_MIN_CELL: usize = 20;
+
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+
+/// Rows of a 5x7 pixel font glyph, top first, with the leftmost pixel in
+/// bit 4; only what coordinates need
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        _ => [0; GLYPH_HEIGHT],
+    }
+}
+
+/// Pixel width of `text` in the 5x7 font, one blank column between glyphs
+fn text_width(text: &str) -> usize {
+    (text.chars().count() * (GLYPH_WIDTH + 1)).saturating_sub(1)
+}
+
+/// Paint `text` in the 5x7 font with its top-left corner at (`left`,
+/// `top`), clipped to the image
+fn paint_text(img: &mut RgbImage, text: &str, left: usize, top: usize, color: Rgb<u8>) {
+    for (i, c) in text.chars().enumerate() {
+        let x0 = left + i * (GLYPH_WIDTH + 1);
+        for (dy, row) in glyph(c).into_iter().enumerate() {
+            for dx in 0..GLYPH_WIDTH {
+                let (px, py) = ((x0 + dx) as u32, (top + dy) as u32);
+                if row & (0x10 >> dx) != 0 && px < img.width() && py < img.height() {
+                    img.put_pixel(px, py, color);
+                }
+            }
+        }
+    }
+}
+
+/// Fill one marker shape in the middle of a cell
+fn paint_marker(
+    img: &mut RgbImage,
+    (x, y): (usize, usize),
+    cell_size: usize,
+    style: MarkerStyle,
+    color: Rgb<u8>,
+) {
+    let r = (cell_size as f64 * 0.3).max(1.0);
+    let cx = (x * cell_size) as f64 + cell_size as f64 / 2.0;
+    let cy = (y * cell_size) as f64 + cell_size as f64 / 2.0;
+    for py in (cy - r).floor() as i64..=(cy + r).ceil() as i64 {
+        for px in (cx - r).floor() as i64..=(cx + r).ceil() as i64 {
+            if px < 0 || py < 0 || px as u32 >= img.width() || py as u32 >= img.height() {
+                continue;
+            }
+            let (ox, oy) = ((px as f64 + 0.5 - cx).abs(), (py as f64 + 0.5 - cy).abs());
+            let inside = match style {
+                MarkerStyle::Circle => ox * ox + oy * oy <= r * r,
+                MarkerStyle::Square => ox <= r && oy <= r,
+                MarkerStyle::Diamond => ox + oy <= r,
+            };
+            if inside {
+                img.put_pixel(px as u32, py as u32, color);
+            }
+        }
+    }
+}
+
+// Raster renderings beyond the plain `draw`
+impl Maze {
+    /// Paint `solution` as `draw` does: in `opts.solution`, or shading
+    /// from start to end when `opts.solution_gradient` is set
+    pub fn draw_solution(
+        &self,
+        img: &mut RgbImage,
+        solution: &[(usize, usize)],
+        opts: &DrawOptions,
+    ) {
+        let steps = solution.len().saturating_sub(1);
+        self.paint_path(img, solution, opts.cell_size, |i| {
+            opts.solution_color(i, steps)
+        });
+    }
+
+    /// The solution the `draw*` methods paint: empty when
+    /// `opts.show_solution` is off or the maze cannot be solved
+    pub(crate) fn drawn_solution(&self, opts: &DrawOptions) -> Vec<(usize, usize)> {
+        if opts.show_solution {
+            self.solve().unwrap_or_default()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Paint the start and end markers from `opts`, centred in their cells
+    /// and 3/5 of a cell across
+    pub fn draw_endpoints(&self, img: &mut RgbImage, cell_size: usize, opts: &DrawOptions) {
+        let start_style = opts.marker_style.unwrap_or(MarkerStyle::Circle);
+        let end_style = opts.marker_style.unwrap_or(MarkerStyle::Square);
+        paint_marker(img, self.start, cell_size, start_style, opts.start_color);
+        paint_marker(img, self.end, cell_size, end_style, opts.end_color);
+    }
+
+    /// Print the coordinates of every cell in the middle of it (see
+    /// `DrawOptions::show_coordinates`): `(x,y)` where that fits, `x,y`
+    /// where it does not and `x` over `y` in the tightest cells. Does
+    /// nothing when `cell_size` is below `COORDINATE_MIN_CELL`.
+    pub fn draw_coordinates(&self, img: &mut RgbImage, cell_size: usize, color: Rgb<u8>) {
+        if cell_size < COORDINATE_MIN_CELL {
+            return;
+        }
+        let room = cell_size.saturating_sub(2);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let lines = [format!("({x},{y})"), format!("{x},{y}")]
+                    .into_iter()
+                    .find(|text| text_width(text) <= room)
+                    .map_or_else(|| vec![x.to_string(), y.to_string()], |text| vec![text]);
+                let height = lines.len() * (GLYPH_HEIGHT + 1) - 1;
+                let top = y * cell_size + (cell_size - height.min(cell_size)) / 2;
+                for (i, line) in lines.iter().enumerate() {
+                    let left = x * cell_size + cell_size.saturating_sub(text_width(line)) / 2;
+                    paint_text(img, line, left, top + i * (GLYPH_HEIGHT + 1), color);
+                }
+            }
+        }
+    }
+
+    /// `draw` encoded as PNG in memory, for callers with no file to write
+    /// to, such as a web server answering with the image
+    pub fn to_png_bytes(&self, opts: &DrawOptions) -> ImageResult<Vec<u8>> {
+        let mut png = Cursor::new(Vec::new());
+        self.draw(opts)
+            .write_with_encoder(PngEncoder::new(&mut png))?;
+        Ok(png.into_inner())
+    }
+
+    /// Like `draw`, but on a transparent background: walls and the start/end
+    /// markers are opaque and the solution uses `opts.solution_alpha`, so the
+    /// picture can be laid over other images. `opts.background` is unused.
+    pub fn draw_rgba(&self, opts: &DrawOptions) -> RgbaImage {
+        let (cell_size, wall_thick) = (opts.cell_size, opts.wall_thickness);
+        let img_w = (self.width * cell_size + wall_thick) as u32;
+        let img_h = (self.height * cell_size + wall_thick) as u32;
+        let mut out = RgbaImage::new(img_w, img_h);
+
+        // Each layer is painted white on black to find the pixels it covers,
+        // which then take the layer's colour, later layers on top. A layer
+        // without a colour keeps the (never black) colours it was painted in.
+        let white = Rgb([255, 255, 255]);
+        let mut layer = |paint: &dyn Fn(&mut RgbImage), color: Option<Rgb<u8>>, alpha: u8| {
+            let mut mask = RgbImage::new(img_w, img_h);
+            paint(&mut mask);
+            for (px, m) in out.pixels_mut().zip(mask.pixels()) {
+                if m.0 != [0; 3] {
+                    let [r, g, b] = color.unwrap_or(*m).0;
+                    *px = Rgba([r, g, b, alpha]);
+                }
+            }
+        };
+        let solution = self.drawn_solution(opts);
+        layer(
+            &|img| self.paint_walls(img, cell_size, wall_thick, white),
+            Some(opts.wall),
+            255,
+        );
+        if opts.solution_gradient {
+            layer(
+                &|img| self.draw_solution(img, &solution, opts),
+                None,
+                opts.solution_alpha,
+            );
+        } else {
+            layer(
+                &|img| self.draw_path(img, &solution, cell_size, white),
+                Some(opts.solution),
+                opts.solution_alpha,
+            );
+        }
+        if opts.show_arrows {
+            layer(
+                &|img| self.draw_arrows(img, &solution, cell_size, white),
+                Some(ARROW_COLOR),
+                opts.solution_alpha,
+            );
+        }
+        let start_style = opts.marker_style.unwrap_or(MarkerStyle::Circle);
+        let end_style = opts.marker_style.unwrap_or(MarkerStyle::Square);
+        layer(
+            &|img| paint_marker(img, self.start, cell_size, start_style, white),
+            Some(opts.start_color),
+            255,
+        );
+        layer(
+            &|img| paint_marker(img, self.end, cell_size, end_style, white),
+            Some(opts.end_color),
+            255,
+        );
+        if opts.padding == 0 {
+            return out;
+        }
+        let p = opts.padding as u32;
+        let mut padded = RgbaImage::new(img_w + 2 * p, img_h + 2 * p);
+        imageops::replace(&mut padded, &out, p.into(), p.into());
+        padded
+    }
+
+    /// Draw only the `cell_w` x `cell_h` cells whose top-left cell is
+    /// (`cell_x`, `cell_y`), clipped to the maze. The crop keeps the real
+    /// walls along its edges, and the parts of the solution and the
+    /// start/end markers that fall inside it are drawn as in `draw`.
+    pub fn draw_region(
+        &self,
+        cell_x: usize,
+        cell_y: usize,
+        cell_w: usize,
+        cell_h: usize,
+        opts: &DrawOptions,
+    ) -> RgbImage {
+        let (x0, y0) = (cell_x.min(self.width), cell_y.min(self.height));
+        let (w, h) = (cell_w.min(self.width - x0), cell_h.min(self.height - y0));
+        let region = Maze {
+            width: w,
+            height: h,
+            vert_walls: self.vert_walls[y0..y0 + h]
+                .iter()
+                .map(|row| row[x0..=x0 + w].to_vec())
+                .collect(),
+            hor_walls: self.hor_walls[y0..=y0 + h]
+                .iter()
+                .map(|row| row[x0..x0 + w].to_vec())
+                .collect(),
+            seed: self.seed,
+            start: self.start,
+            end: self.end,
+            rooms: Vec::new(),
+            portals: Vec::new(),
+            one_way: BTreeSet::new(),
+            doors: Vec::new(),
+            keys: Vec::new(),
+            cell_weights: Vec::new(),
+            fractal_depth: None,
+            toroidal: false,
+        };
+        let local = |(x, y): (usize, usize)| {
+            ((x0..x0 + w).contains(&x) && (y0..y0 + h).contains(&y)).then(|| (x - x0, y - y0))
+        };
+
+        let mut img = region.draw_no_solution(opts);
+        let solution = self.drawn_solution(opts);
+        let steps = solution.len().saturating_sub(1);
+        let mut offset = 0;
+        for run in solution.chunk_by(|&a, &b| local(a).is_some() == local(b).is_some()) {
+            let first = offset;
+            offset += run.len();
+            let Some(run) = run.iter().map(|&c| local(c)).collect::<Option<Vec<_>>>() else {
+                continue;
+            };
+            region.paint_path(&mut img, &run, opts.cell_size, |i| {
+                opts.solution_color(first + i, steps)
+            });
+            if opts.show_arrows {
+                region.draw_arrows(&mut img, &run, opts.cell_size, ARROW_COLOR);
+            }
+        }
+        let start_style = opts.marker_style.unwrap_or(MarkerStyle::Circle);
+        let end_style = opts.marker_style.unwrap_or(MarkerStyle::Square);
+        if let Some(cell) = local(self.start) {
+            paint_marker(
+                &mut img,
+                cell,
+                opts.cell_size,
+                start_style,
+                opts.start_color,
+            );
+        }
+        if let Some(cell) = local(self.end) {
+            paint_marker(&mut img, cell, opts.cell_size, end_style, opts.end_color);
+        }
+        opts.pad(img)
+    }
+
+    /// Paint triangular arrowheads along a path of adjacent cells: one on
+    /// every cell where the path turns, pointing the way it leaves, and one
+    /// on the last cell, pointing the way it arrives. They are about 3/8 of
+    /// a cell long, so they scale with `cell_size`.
+    pub fn draw_arrows(
+        &self,
+        img: &mut RgbImage,
+        path: &[(usize, usize)],
+        cell_size: usize,
+        color: Rgb<u8>,
+    ) {
+        // Unit step between two cells, `None` across a toroidal wrap
+        let dir = |a: (usize, usize), b: (usize, usize)| {
+            let d = (b.0 as isize - a.0 as isize, b.1 as isize - a.1 as isize);
+            (d.0.abs() + d.1.abs() == 1).then_some(d)
+        };
+        let mut arrows = Vec::new();
+        for w in path.windows(3) {
+            let din = dir(w[0], w[1]);
+            if let Some(dout) = dir(w[1], w[2])
+                && din != Some(dout)
+            {
+                arrows.push((w[1], dout));
+            }
+        }
+        if let [.., a, b] = path
+            && let Some(d) = dir(*a, *b)
+        {
+            arrows.push((*b, d));
+        }
+
+        let size = (cell_size as f64 * 0.375).max(2.0);
+        for ((x, y), (dx, dy)) in arrows {
+            let cx = (x * cell_size) as f64 + cell_size as f64 / 2.0;
+            let cy = (y * cell_size) as f64 + cell_size as f64 / 2.0;
+            paint_arrow(img, (cx, cy), (dx as f64, dy as f64), size, color);
+        }
+    }
+
+    /// Paint an arrowhead on every one-way passage, centred on the wall gap
+    /// and pointing the way it may be walked. Passages across a toroidal
+    /// wrap get theirs on the edge of the `from` cell.
+    pub fn draw_one_way(&self, img: &mut RgbImage, cell_size: usize, color: Rgb<u8>) {
+        let size = (cell_size as f64 * 0.375).max(2.0);
+        let half = cell_size as f64 / 2.0;
+        for &(from, to) in &self.one_way {
+            let Some((dx, dy)) = [('R', (1, 0)), ('L', (-1, 0)), ('D', (0, 1)), ('U', (0, -1))]
+                .into_iter()
+                .find(|&(d, _)| self.step(from, d) == Some(to))
+                .map(|(_, d)| d)
+            else {
+                continue;
+            };
+            let cx = (from.0 * cell_size) as f64 + half + dx as f64 * half;
+            let cy = (from.1 * cell_size) as f64 + half + dy as f64 * half;
+            paint_arrow(img, (cx, cy), (dx as f64, dy as f64), size, color);
+        }
+    }
+
+    /// Draw the maze with every cell filled by its BFS distance from
+    /// `self.start`, blue when near and red when far; unreachable cells keep
+    /// the background colour. Walls are drawn on top in the wall colour, and
+    /// a legend bar below the maze runs from distance 0 on the left to the
+    /// farthest cell on the right.
+    pub fn draw_heatmap(&self, opts: &DrawOptions) -> RgbImage {
+        let (cell_size, wall_thick) = (opts.cell_size, opts.wall_thickness);
+        let img_w = (self.width * cell_size + wall_thick) as u32;
+        let maze_h = (self.height * cell_size + wall_thick) as u32;
+        let legend_h = (cell_size as u32 / 2).max(6);
+        let gap = (wall_thick as u32).max(2);
+        let mut img = RgbImage::from_pixel(img_w, maze_h + gap + legend_h, opts.background);
+
+        let dist = self.distances_from(self.start);
+        let max = dist
+            .iter()
+            .flatten()
+            .filter(|&&d| d != usize::MAX)
+            .max()
+            .copied()
+            .unwrap_or(0)
+            .max(1);
+        for (y, row) in dist.iter().enumerate() {
+            for (x, &d) in row.iter().enumerate() {
+                if d == usize::MAX {
+                    continue;
+                }
+                let color = lerp_color(HEAT_NEAR, HEAT_FAR, d as f64 / max as f64);
+                let (x0, y0) = ((x * cell_size) as u32, (y * cell_size) as u32);
+                for dy in 0..cell_size as u32 {
+                    for dx in 0..cell_size as u32 {
+                        img.put_pixel(x0 + dx, y0 + dy, color);
+                    }
+                }
+            }
+        }
+
+        self.paint_walls(&mut img, cell_size, wall_thick, opts.wall);
+
+        let span = (img_w - 1).max(1) as f64;
+        for x in 0..img_w {
+            let color = lerp_color(HEAT_NEAR, HEAT_FAR, x as f64 / span);
+            for y in maze_h + gap..maze_h + gap + legend_h {
+                img.put_pixel(x, y, color);
+            }
+        }
+        opts.pad(img)
+    }
+
+    /// Walls as a binary height map: every cell, wall and corner post is a
+    /// `cell_size` square (the block layout of `save_mask_image`), white
+    /// (255) where a wall stands and black (0) for the floor, with R = G = B.
+    /// Unlike `draw` there are no markers, solution or theme colours, so
+    /// the image can go straight into a terrain or collision importer.
+    pub fn to_heightmap(&self, cell_size: usize) -> RgbImage {
+        let grid = self.wall_grid();
+        let size = cell_size.max(1);
+        let (img_w, img_h) = (grid[0].len() * size, grid.len() * size);
+        RgbImage::from_fn(img_w as u32, img_h as u32, |px, py| {
+            let high = grid[py as usize / size][px as usize / size];
+            Rgb([if high { 255 } else { 0 }; 3])
+        })
+    }
+}
@@ -0,0 +1,266 @@
+// src/edit.rs
+
+use alloc::vec::Vec;
+
+use crate::{Maze, MazeError, MazeLike, WallSide};
+
+// Manual wall editing
+impl Maze {
+    /// Raise the `side` wall of cell (`x`, `y`). The wall is shared with the
+    /// neighbour on that side, so e.g. the east wall of (x, y) is also the
+    /// west wall of (x + 1, y); on a toroidal maze both copies of a
+    /// wrap-around wall change together. Fails with
+    /// `MazeError::OutOfBounds` when the cell is outside the grid.
+    pub fn add_wall(&mut self, x: usize, y: usize, side: WallSide) -> Result<(), MazeError> {
+        self.set_side(x, y, side, true)
+    }
+
+    /// Knock down the `side` wall of cell (`x`, `y`); see `add_wall`.
+    /// Removing an outer wall of a non-toroidal maze opens the boundary
+    /// without adding a neighbour.
+    pub fn remove_wall(&mut self, x: usize, y: usize, side: WallSide) -> Result<(), MazeError> {
+        self.set_side(x, y, side, false)
+    }
+
+    fn set_side(
+        &mut self,
+        x: usize,
+        y: usize,
+        side: WallSide,
+        standing: bool,
+    ) -> Result<(), MazeError> {
+        if !self.in_bounds((x, y)) {
+            return Err(MazeError::OutOfBounds { x, y });
+        }
+        match side.canonical(x, y) {
+            (x, y, WallSide::North) => {
+                self.hor_walls[y][x] = standing;
+                if self.wraps_y() && (y == 0 || y == self.height) {
+                    self.hor_walls[self.height - y][x] = standing;
+                }
+            }
+            (x, y, _) => {
+                self.vert_walls[y][x] = standing;
+                if self.wraps_x() && (x == 0 || x == self.width) {
+                    self.vert_walls[y][self.width - x] = standing;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One wall change made through a `MazeEditor`, with enough state to undo it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditOp {
+    pub x: usize,
+    pub y: usize,
+    pub side: WallSide,
+    /// Whether the wall stood before the edit
+    pub was_standing: bool,
+    /// Whether the wall stands after the edit
+    pub standing: bool,
+}
+
+/// Wraps a `Maze` for interactive editing, recording every `add_wall` and
+/// `remove_wall` so it can be undone and redone. Making a new edit after
+/// an undo discards the redo stack.
+#[derive(Debug, Clone)]
+pub struct MazeEditor {
+    maze: Maze,
+    history: Vec<EditOp>,
+    future: Vec<EditOp>,
+}
+
+impl MazeEditor {
+    pub fn new(maze: Maze) -> Self {
+        Self {
+            maze,
+            history: Vec::new(),
+            future: Vec::new(),
+        }
+    }
+
+    /// The maze as edited so far
+    pub fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    /// Edits that `undo` would revert, oldest first
+    pub fn history(&self) -> &[EditOp] {
+        &self.history
+    }
+
+    /// `Maze::add_wall` on the wrapped maze, recorded for undo
+    pub fn add_wall(&mut self, x: usize, y: usize, side: WallSide) -> Result<(), MazeError> {
+        self.apply(x, y, side, true)
+    }
+
+    /// `Maze::remove_wall` on the wrapped maze, recorded for undo
+    pub fn remove_wall(&mut self, x: usize, y: usize, side: WallSide) -> Result<(), MazeError> {
+        self.apply(x, y, side, false)
+    }
+
+    /// Revert the most recent edit, returning it, or `None` when there is
+    /// nothing left to undo
+    pub fn undo(&mut self) -> Option<EditOp> {
+        let op = self.history.pop()?;
+        self.maze
+            .set_side(op.x, op.y, op.side, op.was_standing)
+            .expect("recorded edits are in bounds");
+        self.future.push(op);
+        Some(op)
+    }
+
+    /// Re-apply the most recently undone edit, returning it, or `None` when
+    /// nothing has been undone since the last edit
+    pub fn redo(&mut self) -> Option<EditOp> {
+        let op = self.future.pop()?;
+        self.maze
+            .set_side(op.x, op.y, op.side, op.standing)
+            .expect("recorded edits are in bounds");
+        self.history.push(op);
+        Some(op)
+    }
+
+    /// Finish editing and take the maze, dropping the history
+    pub fn commit(self) -> Maze {
+        self.maze
+    }
+
+    fn apply(
+        &mut self,
+        x: usize,
+        y: usize,
+        side: WallSide,
+        standing: bool,
+    ) -> Result<(), MazeError> {
+        if !self.maze.in_bounds((x, y)) {
+            return Err(MazeError::OutOfBounds { x, y });
+        }
+        let was_standing = self.maze.has_wall(x, y, side);
+        self.maze.set_side(x, y, side, standing)?;
+        self.history.push(EditOp {
+            x,
+            y,
+            side,
+            was_standing,
+            standing,
+        });
+        self.future.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Maze, MazeEditor, MazeError, MazeLike, WallSide};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    fn generated() -> Maze {
+        let mut maze = Maze::new(6, 5).unwrap();
+        maze.generate_with_seed(2);
+        maze
+    }
+
+    #[test]
+    fn shared_walls_change_on_both_sides() {
+        let mut maze = generated();
+        let original = maze.clone();
+        maze.remove_wall(2, 3, WallSide::East).unwrap();
+        assert!(!maze.has_wall(3, 3, WallSide::West));
+        assert!(maze.open_neighbors((3, 3)).contains(&(2, 3)));
+        maze.add_wall(2, 3, WallSide::South).unwrap();
+        assert!(maze.has_wall(2, 4, WallSide::North));
+        assert!(!maze.open_neighbors((2, 4)).contains(&(2, 3)));
+
+        // Put both walls back from the other side
+        for (x, y, side, standing) in [
+            (3, 3, WallSide::West, original.vert_walls[3][3]),
+            (2, 4, WallSide::North, original.hor_walls[4][2]),
+        ] {
+            if standing {
+                maze.add_wall(x, y, side).unwrap();
+            } else {
+                maze.remove_wall(x, y, side).unwrap();
+            }
+        }
+        assert_eq!(maze, original);
+    }
+
+    #[test]
+    fn wrap_around_walls_change_on_both_edges() {
+        let mut maze = Maze::new(4, 4).unwrap();
+        maze.generate_toroidal();
+        maze.remove_wall(0, 1, WallSide::West).unwrap();
+        assert!(!maze.vert_walls[1][0] && !maze.vert_walls[1][4]);
+        maze.add_wall(2, 3, WallSide::South).unwrap();
+        assert!(maze.hor_walls[0][2] && maze.hor_walls[4][2]);
+    }
+
+    #[test]
+    fn cells_past_the_edge_are_out_of_bounds() {
+        let mut maze = generated();
+        let original = maze.clone();
+        assert_eq!(
+            maze.add_wall(6, 0, WallSide::West),
+            Err(MazeError::OutOfBounds { x: 6, y: 0 })
+        );
+        assert_eq!(
+            maze.remove_wall(1, 5, WallSide::North),
+            Err(MazeError::OutOfBounds { x: 1, y: 5 })
+        );
+        assert_eq!(maze, original);
+        // The outer walls of the edge cells themselves can be edited
+        maze.remove_wall(5, 4, WallSide::East).unwrap();
+        assert!(!maze.vert_walls[4][6]);
+    }
+
+    #[test]
+    fn undo_and_redo_round_trip() {
+        let original = generated();
+        let mut editor = MazeEditor::new(original.clone());
+        let mut rng = StdRng::seed_from_u64(9);
+        let sides = [
+            WallSide::North,
+            WallSide::South,
+            WallSide::East,
+            WallSide::West,
+        ];
+        for _ in 0..50 {
+            let (x, y) = (rng.random_range(0..6), rng.random_range(0..5));
+            let side = sides[rng.random_range(0..4)];
+            if rng.random_bool(0.5) {
+                editor.add_wall(x, y, side).unwrap();
+            } else {
+                editor.remove_wall(x, y, side).unwrap();
+            }
+        }
+        let edited = editor.maze().clone();
+        assert_eq!(editor.history().len(), 50);
+
+        while editor.undo().is_some() {}
+        assert_eq!(editor.maze(), &original);
+        assert!(editor.history().is_empty());
+        while editor.redo().is_some() {}
+        assert_eq!(editor.maze(), &edited);
+        assert_eq!(editor.commit(), edited);
+    }
+
+    #[test]
+    fn new_edit_clears_redo() {
+        let mut editor = MazeEditor::new(generated());
+        editor.add_wall(1, 1, WallSide::East).unwrap();
+        editor.remove_wall(2, 2, WallSide::North).unwrap();
+        let undone = editor.undo().unwrap();
+        assert_eq!((undone.x, undone.y, undone.side), (2, 2, WallSide::North));
+        editor.add_wall(3, 3, WallSide::West).unwrap();
+        assert_eq!(editor.redo(), None);
+        assert_eq!(editor.history().len(), 2);
+        assert_eq!(
+            editor.add_wall(9, 0, WallSide::West),
+            Err(MazeError::OutOfBounds { x: 9, y: 0 })
+        );
+        assert_eq!(editor.history().len(), 2);
+    }
+}
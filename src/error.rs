@@ -0,0 +1,198 @@
+// src/error.rs
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+/// Errors produced by fallible maze operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MazeError {
+    /// Text input could not be read as a grid of 3×3 cell blocks; `line` is 1-based
+    MalformedAscii { line: usize },
+    /// Two neighbouring cells disagree about the wall they share
+    InconsistentWalls { x: usize, y: usize },
+    /// An adjacency list joins two cell indices that are not orthogonal
+    /// neighbours, or names a cell outside the grid
+    NonAdjacentEdge { from: usize, to: usize },
+    /// A maze needs at least one row and one column of cells
+    ZeroDimension { width: usize, height: usize },
+    /// No passage leads from `from` to `to`
+    Unreachable {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+    /// A cell (or a region's far corner) lies outside the grid
+    OutOfBounds { x: usize, y: usize },
+    /// A mask's size does not match the maze, or it contains no cells
+    InvalidMask,
+    /// A wall-following solver used up its step budget without reaching
+    /// the goal, which happens when a loop keeps it away from the exit
+    SolverLooped,
+    /// A wall image is not `2 * width + 1` by `2 * height + 1` pixels for
+    /// some non-zero maze size
+    InvalidMaskImage { width: u32, height: u32 },
+    /// An image could not be read; holds the decoder's message
+    ImageLoad(String),
+    /// RON input could not be read as a maze; holds the parser's message
+    InvalidRon(String),
+    /// A `maze.toml` file could not be read or holds a bad value; holds
+    /// the path and the problem
+    InvalidConfig(String),
+    /// CSV input could not be read as a maze; lists every problem found
+    InvalidCsv(Vec<String>),
+    /// No maze reached the requested difficulty within the allowed attempts
+    DifficultyNotAchievable,
+    /// No maze had a solution length in the requested range within the
+    /// allowed attempts
+    PathLengthNotAchievable,
+    /// Two cells meant to share a passage are not orthogonal neighbours
+    NotAdjacent {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+    /// A door or key uses an ID of `MAX_KEYS` or more
+    InvalidKeyId(u8),
+    /// `solve_with_timeout` ran out of time; `partial` leads from the start
+    /// to the explored cell nearest the goal
+    SolverTimeout { partial: Vec<(usize, usize)> },
+    /// Two `MazeBuilder` options that each choose how the maze is carved
+    /// were both set; holds the setter names
+    ConflictingOptions {
+        first: &'static str,
+        second: &'static str,
+    },
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeError::MalformedAscii { line } => {
+                write!(f, "malformed ASCII maze at line {line}")
+            }
+            MazeError::InconsistentWalls { x, y } => {
+                write!(
+                    f,
+                    "cell ({x}, {y}) disagrees with its neighbour about a wall"
+                )
+            }
+            MazeError::NonAdjacentEdge { from, to } => {
+                write!(f, "cells {from} and {to} are not adjacent")
+            }
+            MazeError::ZeroDimension { width, height } => {
+                write!(f, "a {width}x{height} maze has no cells")
+            }
+            MazeError::Unreachable { from, to } => {
+                write!(f, "no path from {from:?} to {to:?}")
+            }
+            MazeError::OutOfBounds { x, y } => {
+                write!(f, "cell ({x}, {y}) is outside the maze")
+            }
+            MazeError::InvalidMask => {
+                write!(f, "mask does not match the maze size or has no cells")
+            }
+            MazeError::SolverLooped => {
+                write!(f, "wall follower gave up without reaching the goal")
+            }
+            MazeError::InvalidMaskImage { width, height } => {
+                write!(
+                    f,
+                    "a {width}x{height} image does not have odd sides of at least 3 pixels"
+                )
+            }
+            MazeError::ImageLoad(msg) => write!(f, "could not read image: {msg}"),
+            MazeError::InvalidRon(msg) => write!(f, "invalid maze RON: {msg}"),
+            MazeError::InvalidConfig(msg) => write!(f, "invalid config: {msg}"),
+            MazeError::InvalidCsv(errors) => {
+                write!(f, "invalid maze CSV: {}", errors.join("; "))
+            }
+            MazeError::DifficultyNotAchievable => {
+                write!(f, "no attempt reached the requested difficulty")
+            }
+            MazeError::PathLengthNotAchievable => {
+                write!(f, "no attempt had a solution length in the requested range")
+            }
+            MazeError::NotAdjacent { from, to } => {
+                write!(f, "cells {from:?} and {to:?} are not neighbours")
+            }
+            MazeError::InvalidKeyId(id) => {
+                write!(f, "key ID {id} is not below {}", crate::MAX_KEYS)
+            }
+            MazeError::SolverTimeout { partial } => {
+                write!(
+                    f,
+                    "solver timed out with a partial path of {} cells",
+                    partial.len()
+                )
+            }
+            MazeError::ConflictingOptions { first, second } => {
+                write!(f, "`{first}` cannot be combined with `{second}`")
+            }
+        }
+    }
+}
+
+impl core::error::Error for MazeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::MazeError;
+    use crate::Maze;
+    use alloc::{vec, vec::Vec};
+
+    fn generated(width: usize, height: usize) -> Maze {
+        let mut maze = Maze::new(width, height).unwrap();
+        maze.generate_with_seed(1);
+        maze
+    }
+
+    #[test]
+    fn zero_dimension() {
+        assert_eq!(
+            Maze::new(0, 3),
+            Err(MazeError::ZeroDimension {
+                width: 0,
+                height: 3
+            })
+        );
+        assert_eq!(
+            generated(4, 4).sub_maze(4, 0, 2, 2),
+            Err(MazeError::ZeroDimension {
+                width: 0,
+                height: 2
+            })
+        );
+    }
+
+    #[test]
+    fn unreachable() {
+        let walled = Maze::new(3, 2).unwrap();
+        assert_eq!(
+            walled.solve(),
+            Err(MazeError::Unreachable {
+                from: (0, 0),
+                to: (2, 1)
+            })
+        );
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut maze = generated(4, 4);
+        maze.end = (4, 1);
+        assert_eq!(maze.solve(), Err(MazeError::OutOfBounds { x: 4, y: 1 }));
+        assert_eq!(
+            generated(4, 4).merge(&generated(3, 2), (2, 1)),
+            Err(MazeError::OutOfBounds { x: 4, y: 2 })
+        );
+    }
+
+    #[test]
+    fn invalid_mask() {
+        let mut maze = Maze::new(3, 2).unwrap();
+        let short: Vec<Vec<bool>> = vec![vec![true; 3]];
+        assert_eq!(maze.generate_masked(&short), Err(MazeError::InvalidMask));
+        let empty = vec![vec![false; 3]; 2];
+        assert_eq!(maze.generate_masked(&empty), Err(MazeError::InvalidMask));
+        let ragged = vec![vec![true; 3], vec![true; 2]];
+        assert_eq!(maze.generate_masked(&ragged), Err(MazeError::InvalidMask));
+    }
+}
@@ -0,0 +1,462 @@
+// src/export.rs
+
+use crate::{Maze, MazeError, Segment};
+use alloc::{format, string::String, vec, vec::Vec};
+
+/// Overlay drawn on top of the walls by `Maze::to_svg_with_layers`;
+/// colors are any CSS color string
+#[derive(Debug, Clone, Copy)]
+pub enum SvgLayer<'a> {
+    /// Polyline through the centres of consecutive cells
+    Path(&'a [(usize, usize)], &'a str),
+    /// Like `Path` with the corners rounded by `Maze::smooth_path`
+    SmoothPath(&'a [(usize, usize)], &'a str),
+    /// Small square in the centre of each cell
+    Markers(&'a [(usize, usize)], &'a str),
+}
+
+// Text and vector export formats
+impl Maze {
+    /// Render the maze as text, one 3×3 character block per cell.
+    ///
+    /// Block corners are always `#`, each edge is `#` when that side is
+    /// walled and ` ` when open, and the centre is blank except for `S` in
+    /// the start cell and `E` in the end cell (top-left and bottom-right by
+    /// default), or `X` in a cell that is both. Walls shared by two cells
+    /// therefore show up twice, once in each block.
+    pub fn to_ascii(&self) -> String {
+        let mut out = String::with_capacity((self.width * 3 + 1) * self.height * 3);
+        for y in 0..self.height {
+            let mut rows = [String::new(), String::new(), String::new()];
+            for x in 0..self.width {
+                let wall = |closed: bool| if closed { '#' } else { ' ' };
+                let centre = if (x, y) == self.start && (x, y) == self.end {
+                    'X'
+                } else if (x, y) == self.start {
+                    'S'
+                } else if (x, y) == self.end {
+                    'E'
+                } else {
+                    ' '
+                };
+                rows[0].extend(['#', wall(self.hor_walls[y][x]), '#']);
+                rows[1].extend([
+                    wall(self.vert_walls[y][x]),
+                    centre,
+                    wall(self.vert_walls[y][x + 1]),
+                ]);
+                rows[2].extend(['#', wall(self.hor_walls[y + 1][x]), '#']);
+            }
+            for row in rows {
+                out.push_str(&row);
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Parse the format written by `to_ascii`.
+    ///
+    /// Short lines are padded with spaces (editors like to strip trailing
+    /// whitespace). `S`/`E` in a block centre set `start`/`end` and `X` sets
+    /// both; without them the corners are used. The text carries no seed,
+    /// so `seed` is 0. Each shared wall is
+    /// written twice, so both copies must agree; otherwise the error names
+    /// the cell on the west/north side of the disputed wall.
+    pub fn from_ascii(s: &str) -> Result<Maze, MazeError> {
+        let mut lines: Vec<Vec<char>> = s.lines().map(|l| l.chars().collect()).collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        let cols = lines.iter().map(Vec::len).max().unwrap_or(0);
+        if lines.is_empty() || !lines.len().is_multiple_of(3) || !cols.is_multiple_of(3) {
+            return Err(MazeError::MalformedAscii {
+                line: lines.len().max(1),
+            });
+        }
+        for (i, line) in lines.iter_mut().enumerate() {
+            line.resize(cols, ' ');
+            if line
+                .iter()
+                .any(|c| !matches!(c, '#' | ' ' | 'S' | 'E' | 'X'))
+            {
+                return Err(MazeError::MalformedAscii { line: i + 1 });
+            }
+        }
+
+        let (width, height) = (cols / 3, lines.len() / 3);
+        let mut maze = Maze::new(width, height)?;
+        maze.seed = 0;
+        let wall = |row: usize, col: usize| lines[row][col] == '#';
+        for y in 0..height {
+            for x in 0..width {
+                let (row, col) = (y * 3, x * 3);
+                if x + 1 < width && wall(row + 1, col + 2) != wall(row + 1, col + 3) {
+                    return Err(MazeError::InconsistentWalls { x, y });
+                }
+                if y + 1 < height && wall(row + 2, col + 1) != wall(row + 3, col + 1) {
+                    return Err(MazeError::InconsistentWalls { x, y });
+                }
+                match lines[row + 1][col + 1] {
+                    'S' => maze.start = (x, y),
+                    'E' => maze.end = (x, y),
+                    'X' => (maze.start, maze.end) = ((x, y), (x, y)),
+                    _ => {}
+                }
+                maze.vert_walls[y][x] = wall(row + 1, col);
+                maze.hor_walls[y][x] = wall(row, col + 1);
+            }
+            maze.vert_walls[y][width] = wall(y * 3 + 1, cols - 1);
+        }
+        for x in 0..width {
+            maze.hor_walls[height][x] = wall(lines.len() - 1, x * 3 + 1);
+        }
+        Ok(maze)
+    }
+
+    /// One CSV row per cell, row by row, under the header `x,y,open_north,
+    /// open_south,open_east,open_west,on_solution,distance_from_start,
+    /// is_dead_end`. Flags are `0` or `1`; `on_solution` follows `solve`
+    /// (all `0` when there is no path) and `distance_from_start` is the BFS
+    /// distance from `self.start`, left empty for unreachable cells.
+    pub fn to_csv(&self) -> String {
+        let solution = self.solve().unwrap_or_default();
+        let mut on_solution = vec![vec![false; self.width]; self.height];
+        for &(x, y) in &solution {
+            on_solution[y][x] = true;
+        }
+        let dist = self.distances_from(self.start);
+        let flag = |b: bool| u8::from(b);
+        let mut csv = String::from(
+            "x,y,open_north,open_south,open_east,open_west,on_solution,distance_from_start,is_dead_end\n",
+        );
+        for c in self.cells() {
+            let d = dist[c.y][c.x];
+            let distance = if d == usize::MAX {
+                String::new()
+            } else {
+                format!("{d}")
+            };
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{distance},{}\n",
+                c.x,
+                c.y,
+                flag(c.open_north),
+                flag(c.open_south),
+                flag(c.open_east),
+                flag(c.open_west),
+                flag(on_solution[c.y][c.x]),
+                flag(self.degree((c.x, c.y)) == 1),
+            ));
+        }
+        csv
+    }
+
+    /// Read the CSV written by `to_csv` back into a maze. Columns are found
+    /// by their header names, so they may be reordered and extra ones are
+    /// ignored; only `x`, `y` and the four `open_*` flags (`0`/`1`, or
+    /// `true`/`false` as spreadsheets write them) are used. The size comes
+    /// from the largest `x` and `y`, and every cell must appear exactly
+    /// once. Neighbours must agree about the wall they share, e.g. `open_east`
+    /// of (x, y) must equal `open_west` of (x + 1, y). Edge flags become the
+    /// outer walls, but the result is never `toroidal`, `start`/`end`
+    /// keep their `Maze::new` corners and `seed` is 0. Fails with `MazeError::InvalidCsv`
+    /// listing every problem found.
+    pub fn from_csv(s: &str) -> Result<Maze, MazeError> {
+        const COLUMNS: [&str; 6] = [
+            "x",
+            "y",
+            "open_north",
+            "open_south",
+            "open_east",
+            "open_west",
+        ];
+        let mut errors = Vec::new();
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i + 1, l.trim_end_matches('\r')))
+            .filter(|(_, l)| !l.trim().is_empty());
+        let Some((_, header)) = lines.next() else {
+            return Err(MazeError::InvalidCsv(vec![String::from("no header row")]));
+        };
+        let names: Vec<&str> = header.split(',').map(str::trim).collect();
+        let mut index = [0; 6];
+        for (slot, column) in index.iter_mut().zip(COLUMNS) {
+            match names.iter().position(|&n| n == column) {
+                Some(i) => *slot = i,
+                None => errors.push(format!("header has no \"{column}\" column")),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(MazeError::InvalidCsv(errors));
+        }
+
+        // [x, y] then the open flags in COLUMNS order
+        let mut rows: Vec<(usize, [usize; 2], [bool; 4])> = Vec::new();
+        for (line, text) in lines {
+            let fields: Vec<&str> = text.split(',').map(str::trim).collect();
+            let field = |i: usize| fields.get(index[i]).copied().unwrap_or("");
+            let coords = [0, 1].map(|i| field(i).parse::<usize>().ok());
+            let flags = [2, 3, 4, 5].map(|i| match field(i) {
+                "1" | "true" | "TRUE" | "True" => Some(true),
+                "0" | "false" | "FALSE" | "False" => Some(false),
+                _ => None,
+            });
+            match (coords, flags) {
+                ([Some(x), Some(y)], [Some(n), Some(s), Some(e), Some(w)]) => {
+                    rows.push((line, [x, y], [n, s, e, w]))
+                }
+                _ => errors.push(format!(
+                    "line {line}: expected numbers for x, y and 0/1 flags"
+                )),
+            }
+        }
+        let width = rows.iter().map(|r| r.1[0] + 1).max().unwrap_or(0);
+        let height = rows.iter().map(|r| r.1[1] + 1).max().unwrap_or(0);
+        if rows.is_empty() {
+            errors.push(String::from("no cell rows"));
+        }
+        if !errors.is_empty() {
+            return Err(MazeError::InvalidCsv(errors));
+        }
+
+        let mut cells: Vec<Vec<Option<[bool; 4]>>> = vec![vec![None; width]; height];
+        for &(line, [x, y], open) in &rows {
+            if cells[y][x].replace(open).is_some() {
+                errors.push(format!(
+                    "line {line}: cell ({x}, {y}) appears more than once"
+                ));
+            }
+        }
+        for (y, row) in cells.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if cell.is_none() {
+                    errors.push(format!("cell ({x}, {y}) is missing"));
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(MazeError::InvalidCsv(errors));
+        }
+
+        let open = |x: usize, y: usize| cells[y][x].unwrap_or_default();
+        let mut maze = Maze::new(width, height)?;
+        maze.seed = 0;
+        for y in 0..height {
+            for x in 0..width {
+                let [north, south, east, west] = open(x, y);
+                if x + 1 < width && east != open(x + 1, y)[3] {
+                    errors.push(format!(
+                        "cell ({x}, {y}) has open_east={} but ({}, {y}) has open_west={}",
+                        u8::from(east),
+                        x + 1,
+                        u8::from(!east)
+                    ));
+                }
+                if y + 1 < height && south != open(x, y + 1)[0] {
+                    errors.push(format!(
+                        "cell ({x}, {y}) has open_south={} but ({x}, {}) has open_north={}",
+                        u8::from(south),
+                        y + 1,
+                        u8::from(!south)
+                    ));
+                }
+                maze.vert_walls[y][x] = !west;
+                maze.hor_walls[y][x] = !north;
+                if x + 1 == width {
+                    maze.vert_walls[y][width] = !east;
+                }
+                if y + 1 == height {
+                    maze.hor_walls[height][x] = !south;
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(maze)
+        } else {
+            Err(MazeError::InvalidCsv(errors))
+        }
+    }
+
+    /// Render the maze as a standalone SVG document with the same geometry
+    /// as `draw`. Wall runs are merged into single `<rect>`s, which keeps the
+    /// file small for large mazes; the solution is a `<polyline>` through
+    /// the cell centres.
+    pub fn to_svg(&self, cell_size: usize, wall_thick: usize, show_solution: bool) -> String {
+        let solution = if show_solution {
+            self.solve().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        self.to_svg_with_layers(
+            cell_size,
+            wall_thick,
+            &[SvgLayer::Path(&solution, "#ff0000")],
+        )
+    }
+
+    /// Like `to_svg`, drawing the given layers in order on top of the walls
+    pub fn to_svg_with_layers(
+        &self,
+        cell_size: usize,
+        wall_thick: usize,
+        layers: &[SvgLayer],
+    ) -> String {
+        let img_w = self.width * cell_size + wall_thick;
+        let img_h = self.height * cell_size + wall_thick;
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{img_w}" height="{img_h}" viewBox="0 0 {img_w} {img_h}">"#
+        );
+        svg.push('\n');
+        svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\n");
+
+        svg.push_str("<g fill=\"#000000\">\n");
+        for seg in self.wall_segments() {
+            let (x, y, w, h) = match seg {
+                Segment::Vertical { x, y1, y2 } => (
+                    x * cell_size,
+                    y1 * cell_size,
+                    wall_thick,
+                    (y2 - y1) * cell_size + wall_thick,
+                ),
+                Segment::Horizontal { y, x1, x2 } => (
+                    x1 * cell_size,
+                    y * cell_size,
+                    (x2 - x1) * cell_size + wall_thick,
+                    wall_thick,
+                ),
+            };
+            svg.push_str(&format!(
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\"/>\n"
+            ));
+        }
+        svg.push_str("</g>\n");
+
+        let half = cell_size / 2;
+        for layer in layers {
+            match *layer {
+                SvgLayer::Path(path, color) => {
+                    // One polyline per run of adjacent cells; a toroidal
+                    // wrap step starts a new run
+                    let runs = path.chunk_by(|a, b| a.0.abs_diff(b.0) + a.1.abs_diff(b.1) == 1);
+                    for run in runs {
+                        let points: Vec<String> = run
+                            .iter()
+                            .map(|&(x, y)| {
+                                format!("{},{}", x * cell_size + half, y * cell_size + half)
+                            })
+                            .collect();
+                        svg.push_str(&format!(
+                            "<polyline points=\"{}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"{}\" stroke-linecap=\"square\"/>\n",
+                            points.join(" "),
+                            half.max(1)
+                        ));
+                    }
+                }
+                SvgLayer::SmoothPath(path, color) => {
+                    let runs = path.chunk_by(|a, b| a.0.abs_diff(b.0) + a.1.abs_diff(b.1) == 1);
+                    for run in runs {
+                        let points: Vec<String> = Maze::smooth_path(run)
+                            .iter()
+                            .map(|&(x, y)| {
+                                format!("{:.1},{:.1}", x * cell_size as f64, y * cell_size as f64)
+                            })
+                            .collect();
+                        svg.push_str(&format!(
+                            "<polyline points=\"{}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"{}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>\n",
+                            points.join(" "),
+                            half.max(1)
+                        ));
+                    }
+                }
+                SvgLayer::Markers(cells, color) => {
+                    let size = (cell_size / 4).max(1);
+                    for &(x, y) in cells {
+                        svg.push_str(&format!(
+                            "<rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" fill=\"{color}\"/>\n",
+                            x * cell_size + half - size / 2,
+                            y * cell_size + half - size / 2,
+                        ));
+                    }
+                }
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Maze, MazeError};
+    use alloc::{format, string::String, vec::Vec};
+
+    fn generated(seed: u64) -> Maze {
+        let mut maze = Maze::new(7, 5).unwrap();
+        maze.generate_with_seed(seed);
+        // The text carries no seed, so imports always come back with 0
+        maze.seed = 0;
+        maze
+    }
+
+    #[test]
+    fn ascii_round_trips() -> Result<(), MazeError> {
+        for seed in 0..10 {
+            let m = generated(seed);
+            assert_eq!(Maze::from_ascii(&m.to_ascii())?, m);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ascii_keeps_end_on_the_start_cell() -> Result<(), MazeError> {
+        let mut m = generated(3);
+        m.start = (2, 1);
+        m.end = (2, 1);
+        assert_eq!(Maze::from_ascii(&m.to_ascii())?, m);
+        Ok(())
+    }
+
+    #[test]
+    fn ascii_rejects_bad_shapes() {
+        let text = generated(1).to_ascii();
+        let lines: Vec<&str> = text.lines().collect();
+
+        let short = lines[..lines.len() - 1].join("\n");
+        assert!(matches!(
+            Maze::from_ascii(&short),
+            Err(MazeError::MalformedAscii { .. })
+        ));
+        let narrow: String = lines.iter().map(|l| format!("{}\n", &l[1..])).collect();
+        assert!(matches!(
+            Maze::from_ascii(&narrow),
+            Err(MazeError::MalformedAscii { .. })
+        ));
+        assert_eq!(
+            Maze::from_ascii(&text.replacen(' ', "?", 1)),
+            Err(MazeError::MalformedAscii { line: 2 })
+        );
+        assert!(matches!(
+            Maze::from_ascii(""),
+            Err(MazeError::MalformedAscii { .. })
+        ));
+    }
+
+    #[test]
+    fn ascii_rejects_disagreeing_walls() {
+        let mut m = generated(2);
+        m.vert_walls[0][1] = false;
+        let mut text: Vec<Vec<char>> = m.to_ascii().lines().map(|l| l.chars().collect()).collect();
+        // Close the west copy of the wall between (0, 0) and (1, 0) only
+        text[1][2] = '#';
+        let text: String = text
+            .iter()
+            .map(|l| l.iter().collect::<String>() + "\n")
+            .collect();
+        assert_eq!(
+            Maze::from_ascii(&text),
+            Err(MazeError::InconsistentWalls { x: 0, y: 0 })
+        );
+    }
+}
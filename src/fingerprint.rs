@@ -0,0 +1,41 @@
+// src/fingerprint.rs
+
+use crate::Maze;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl Maze {
+    /// A 64-bit hash of the wall layout for cheap equality checks, e.g. to
+    /// skip redrawing a maze that regenerated unchanged. It is FNV-1a over
+    /// the width and height (little-endian `u64`s) followed by the walls
+    /// packed eight to a byte, `vert_walls` then `hor_walls`, row by row.
+    /// `start`, `end`, `seed` and `toroidal` are left out, so two mazes
+    /// with the same walls always match and any wall difference changes
+    /// the result with overwhelming probability. The value is stable
+    /// across platforms and releases of this crate.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut feed = |byte: u8| {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        };
+        for dim in [self.width as u64, self.height as u64] {
+            dim.to_le_bytes().into_iter().for_each(&mut feed);
+        }
+        let walls = self.vert_walls.iter().chain(&self.hor_walls).flatten();
+        let (mut byte, mut bits) = (0u8, 0);
+        for &wall in walls {
+            byte = byte << 1 | u8::from(wall);
+            bits += 1;
+            if bits == 8 {
+                feed(byte);
+                (byte, bits) = (0, 0);
+            }
+        }
+        if bits > 0 {
+            feed(byte);
+        }
+        hash
+    }
+}
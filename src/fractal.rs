@@ -0,0 +1,144 @@
+// src/fractal.rs
+
+use crate::Maze;
+#[cfg(feature = "std")]
+use crate::Segment;
+use alloc::{vec, vec::Vec};
+use rand::{Rng, seq::SliceRandom};
+
+/// Cells `x..x + w` by `y..y + h`, as (x, y, w, h)
+type Rect = (usize, usize, usize, usize);
+
+/// The parts `generate_fractal` splits `rect` into: each side of at least
+/// two cells is halved, giving two or four parts (row-major), or none for
+/// a single cell
+fn split((x, y, w, h): Rect) -> Vec<Rect> {
+    let cols = if w >= 2 {
+        vec![(x, w / 2), (x + w / 2, w - w / 2)]
+    } else {
+        vec![(x, w)]
+    };
+    let rows = if h >= 2 {
+        vec![(y, h / 2), (y + h / 2, h - h / 2)]
+    } else {
+        vec![(y, h)]
+    };
+    if cols.len() * rows.len() < 2 {
+        return Vec::new();
+    }
+    rows.iter()
+        .flat_map(|&(y, h)| cols.iter().map(move |&(x, w)| (x, y, w, h)))
+        .collect()
+}
+
+/// Whether `b` lies directly right of or below `a`, sharing a boundary
+fn touching(a: Rect, b: Rect) -> bool {
+    (a.0 + a.2 == b.0 && a.1 == b.1) || (a.1 + a.3 == b.1 && a.0 == b.0)
+}
+
+impl Maze {
+    /// Fractal maze: halve the grid into quadrants `depth` times, build
+    /// each innermost region as an independent backtracked maze, then join
+    /// sibling regions level by level through one opening in each of
+    /// three of their four shared boundaries (all four would close a
+    /// loop). The splits show up at every scale; `depth` 0 is a single
+    /// region. Regions are built apart, so on a toroidal maze passages
+    /// only wrap at `depth` 0. Sets `fractal_depth`, which
+    /// `DrawOptions::fractal_coloring` reads.
+    pub fn generate_fractal(&mut self, depth: u32) {
+        let mut rng = self.seeded_rng();
+        // Every cell outside the region being built counts as visited,
+        // which keeps its backtracker inside it
+        let mut visited = vec![vec![true; self.width]; self.height];
+        self.fractal_region(
+            (0, 0, self.width, self.height),
+            depth,
+            &mut visited,
+            &mut rng,
+        );
+        self.fractal_depth = Some(depth);
+    }
+
+    fn fractal_region(
+        &mut self,
+        rect: Rect,
+        depth: u32,
+        visited: &mut [Vec<bool>],
+        rng: &mut impl Rng,
+    ) {
+        let parts = if depth > 0 { split(rect) } else { Vec::new() };
+        if parts.is_empty() {
+            let (x0, y0, w, h) = rect;
+            for row in &mut visited[y0..y0 + h] {
+                row[x0..x0 + w].fill(false);
+            }
+            let start = (rng.random_range(x0..x0 + w), rng.random_range(y0..y0 + h));
+            visited[start.1][start.0] = true;
+            self.backtrack_from(start, visited, rng, &mut || {});
+            return;
+        }
+        for &part in &parts {
+            self.fractal_region(part, depth - 1, visited, rng);
+        }
+        let mut boundaries: Vec<_> = parts
+            .iter()
+            .flat_map(|&a| parts.iter().map(move |&b| (a, b)))
+            .filter(|&(a, b)| touching(a, b))
+            .collect();
+        boundaries.shuffle(rng);
+        boundaries.truncate(parts.len() - 1);
+        for (a, b) in boundaries {
+            if a.1 == b.1 {
+                let y = rng.random_range(a.1..a.1 + a.3);
+                self.carve((b.0 - 1, y), (b.0, y));
+            } else {
+                let x = rng.random_range(a.0..a.0 + a.2);
+                self.carve((x, b.1 - 1), (x, b.1));
+            }
+        }
+    }
+
+    /// The lines `generate_fractal` split the grid along, each with its
+    /// level (0 for the first split); empty unless `fractal_depth` is set
+    #[cfg(feature = "std")]
+    pub(crate) fn fractal_lines(&self) -> Vec<(u32, Segment)> {
+        let mut out = Vec::new();
+        let Some(depth) = self.fractal_depth else {
+            return out;
+        };
+        let mut pending = vec![((0, 0, self.width, self.height), 0)];
+        while let Some((rect @ (x, y, w, h), level)) = pending.pop() {
+            if level >= depth {
+                continue;
+            }
+            let parts = split(rect);
+            if parts.is_empty() {
+                continue;
+            }
+            if w >= 2 {
+                let x = x + w / 2;
+                out.push((
+                    level,
+                    Segment::Vertical {
+                        x,
+                        y1: y,
+                        y2: y + h,
+                    },
+                ));
+            }
+            if h >= 2 {
+                let y = y + h / 2;
+                out.push((
+                    level,
+                    Segment::Horizontal {
+                        y,
+                        x1: x,
+                        x2: x + w,
+                    },
+                ));
+            }
+            pending.extend(parts.into_iter().map(|p| (p, level + 1)));
+        }
+        out
+    }
+}
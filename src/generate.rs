@@ -0,0 +1,733 @@
+// src/generate.rs
+
+use crate::Maze;
+use alloc::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+#[cfg(feature = "std")]
+use clap::ValueEnum;
+use core::{cmp::Ordering, str::FromStr};
+use rand::{
+    Rng, SeedableRng,
+    rngs::StdRng,
+    seq::{IndexedRandom, SliceRandom},
+};
+use serde::{Deserialize, Serialize};
+
+/// Every generation strategy `Maze` implements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "std", derive(ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum Algorithm {
+    /// Depth-first recursive backtracking
+    #[default]
+    Dfs,
+    /// Randomized Prim's
+    Prim,
+    /// Randomized Kruskal's
+    Kruskal,
+    /// Wilson's loop-erased random walk
+    Wilson,
+    /// Aldous-Broder random walk
+    AldousBroder,
+    /// Sidewinder row runs
+    Sidewinder,
+    /// Binary tree
+    BinaryTree,
+    /// Hunt-and-kill
+    HuntAndKill,
+    /// Recursive division
+    RecursiveDivision,
+    /// Eller's row-by-row sets
+    Eller,
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Algorithm as ValueEnum>::from_str(s, true)
+    }
+}
+
+/// Which pair of directions `generate_binary_tree` carves towards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BinaryTreeBias {
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+impl FromStr for BinaryTreeBias {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "north-east" | "ne" => Ok(BinaryTreeBias::NorthEast),
+            "north-west" | "nw" => Ok(BinaryTreeBias::NorthWest),
+            "south-east" | "se" => Ok(BinaryTreeBias::SouthEast),
+            "south-west" | "sw" => Ok(BinaryTreeBias::SouthWest),
+            _ => Err(format!("unknown binary tree bias `{s}`")),
+        }
+    }
+}
+
+/// Union-find over cell indices, used by Kruskal's and room joining
+pub(crate) struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+}
+
+impl DisjointSet {
+    pub(crate) fn new(n: usize) -> Self {
+        DisjointSet {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+        }
+    }
+
+    pub(crate) fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// Merge the sets holding `a` and `b`; false if they were already joined
+    pub(crate) fn union(&mut self, a: usize, b: usize) -> bool {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return false;
+        }
+        match self.rank[ra].cmp(&self.rank[rb]) {
+            Ordering::Less => self.parent[ra] = rb,
+            Ordering::Greater => self.parent[rb] = ra,
+            Ordering::Equal => {
+                self.parent[rb] = ra;
+                self.rank[ra] += 1;
+            }
+        }
+        true
+    }
+}
+
+// Alternative generation strategies; all of them clear walls using the
+// same `vert_walls`/`hor_walls` convention as `Maze::generate`.
+impl Maze {
+    /// Whether the left and right edges are joined. Wrapping needs at least
+    /// three columns, otherwise the wrap wall and the interior wall would
+    /// separate the same pair of cells.
+    pub(crate) fn wraps_x(&self) -> bool {
+        self.toroidal && self.width >= 3
+    }
+
+    /// Whether the top and bottom edges are joined (see `wraps_x`)
+    pub(crate) fn wraps_y(&self) -> bool {
+        self.toroidal && self.height >= 3
+    }
+
+    /// Orthogonal neighbours of a cell, walls ignored. On a toroidal maze
+    /// the edge cells also neighbour the cells on the opposite edge.
+    pub(crate) fn grid_neighbors(&self, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+        let mut out = Vec::with_capacity(4);
+        if x + 1 < self.width {
+            out.push((x + 1, y));
+        } else if self.wraps_x() {
+            out.push((0, y));
+        }
+        if x > 0 {
+            out.push((x - 1, y));
+        } else if self.wraps_x() {
+            out.push((self.width - 1, y));
+        }
+        if y + 1 < self.height {
+            out.push((x, y + 1));
+        } else if self.wraps_y() {
+            out.push((x, 0));
+        }
+        if y > 0 {
+            out.push((x, y - 1));
+        } else if self.wraps_y() {
+            out.push((x, self.height - 1));
+        }
+        out
+    }
+
+    /// Remove the wall shared by two adjacent cells. A wrap-around wall is
+    /// stored twice, as the west boundary and the east boundary (or north
+    /// and south), and both copies are cleared.
+    pub(crate) fn carve(&mut self, (x1, y1): (usize, usize), (x2, y2): (usize, usize)) {
+        if y1 == y2 {
+            if x1.abs_diff(x2) > 1 {
+                self.vert_walls[y1][0] = false;
+                self.vert_walls[y1][self.width] = false;
+            } else {
+                self.vert_walls[y1][x1.max(x2)] = false;
+            }
+        } else if y1.abs_diff(y2) > 1 {
+            self.hor_walls[0][x1] = false;
+            self.hor_walls[self.height][x1] = false;
+        } else {
+            self.hor_walls[y1.max(y2)][x1] = false;
+        }
+    }
+
+    /// Recursive backtracker on a torus: the left edge connects to the right
+    /// and the top edge to the bottom, so passages may leave one side of the
+    /// grid and come back in on the other. Sets `toroidal`, which the solvers
+    /// and analysis honour from then on.
+    pub fn generate_toroidal(&mut self) {
+        self.toroidal = true;
+        self.generate();
+    }
+
+    /// Recursive backtracker whose stack never holds more than `max_depth`
+    /// cells (at least 1). A branch that reaches the limit is abandoned as
+    /// if boxed in; once the search runs dry it restarts from a random
+    /// unvisited cell bordering the visited region, opening a wall into it.
+    /// Low limits trade the long winding corridors of `generate` for many
+    /// short branches, and bound the stack on very large grids.
+    pub fn generate_dfs_limited(&mut self, max_depth: usize) {
+        let max_depth = max_depth.max(1);
+        let mut rng = self.seeded_rng();
+        let mut visited = vec![vec![false; self.width]; self.height];
+        visited[0][0] = true;
+        let mut stack = vec![(0, 0)];
+        // (visited, unvisited) neighbour pairs left behind at the depth limit
+        let mut frontier = Vec::new();
+
+        loop {
+            while let Some(&cell) = stack.last() {
+                let unvisited: Vec<_> = self
+                    .grid_neighbors(cell)
+                    .into_iter()
+                    .filter(|&(nx, ny)| !visited[ny][nx])
+                    .collect();
+                match unvisited.choose(&mut rng) {
+                    Some(&next) if stack.len() < max_depth => {
+                        self.carve(cell, next);
+                        visited[next.1][next.0] = true;
+                        stack.push(next);
+                    }
+                    _ => {
+                        frontier.extend(unvisited.into_iter().map(|n| (cell, n)));
+                        stack.pop();
+                    }
+                }
+            }
+
+            // Restart from a random frontier cell that is still unvisited
+            let mut restart = None;
+            while !frontier.is_empty() {
+                let (from, to) = frontier.swap_remove(rng.random_range(0..frontier.len()));
+                if !visited[to.1][to.0] {
+                    restart = Some((from, to));
+                    break;
+                }
+            }
+            let Some((from, to)) = restart else {
+                break;
+            };
+            self.carve(from, to);
+            visited[to.1][to.0] = true;
+            stack.push(to);
+        }
+    }
+
+    /// Randomized Prim's: grow from a random cell, repeatedly opening a random
+    /// frontier wall that leads into an unvisited cell
+    pub fn generate_prim(&mut self) {
+        let mut rng = self.seeded_rng();
+        let mut visited = vec![vec![false; self.width]; self.height];
+        let start = (
+            rng.random_range(0..self.width),
+            rng.random_range(0..self.height),
+        );
+        visited[start.1][start.0] = true;
+
+        let mut frontier: Vec<_> = self
+            .grid_neighbors(start)
+            .into_iter()
+            .map(|n| (start, n))
+            .collect();
+
+        while !frontier.is_empty() {
+            let (from, to) = frontier.swap_remove(rng.random_range(0..frontier.len()));
+            if visited[to.1][to.0] {
+                continue;
+            }
+            self.carve(from, to);
+            visited[to.1][to.0] = true;
+            for n in self.grid_neighbors(to) {
+                if !visited[n.1][n.0] {
+                    frontier.push((to, n));
+                }
+            }
+        }
+    }
+
+    /// Randomized Kruskal's: shuffle every interior wall and open it whenever
+    /// the two cells it separates are not yet connected
+    pub fn generate_kruskal(&mut self) {
+        let mut rng = self.seeded_rng();
+        let mut walls = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if x + 1 < self.width {
+                    walls.push(((x, y), (x + 1, y)));
+                }
+                if y + 1 < self.height {
+                    walls.push(((x, y), (x, y + 1)));
+                }
+            }
+        }
+        walls.shuffle(&mut rng);
+
+        let mut sets = DisjointSet::new(self.width * self.height);
+        for (a, b) in walls {
+            if sets.union(a.1 * self.width + a.0, b.1 * self.width + b.0) {
+                self.carve(a, b);
+            }
+        }
+    }
+
+    /// Wilson's algorithm: loop-erased random walks from unvisited cells until
+    /// they hit the tree, giving an unbiased uniform spanning tree
+    pub fn generate_wilson(&mut self) {
+        self.generate_wilson_with_progress(|_, _| {});
+    }
+
+    /// Same as `generate_wilson`, calling `progress(cells_visited, total_cells)`
+    /// every time a walk is added to the tree
+    pub fn generate_wilson_with_progress(&mut self, mut progress: impl FnMut(usize, usize)) {
+        let mut rng = self.seeded_rng();
+        let total = self.width * self.height;
+        let mut in_tree = vec![false; total];
+        // Exit taken from each cell on the current walk; overwriting an entry
+        // when the walk revisits a cell is what erases the loop
+        let mut next = vec![0; total];
+
+        let mut order: Vec<usize> = (0..total).collect();
+        order.shuffle(&mut rng);
+        in_tree[order[0]] = true;
+        let mut visited = 1;
+        progress(visited, total);
+
+        for &start in &order[1..] {
+            let mut cur = start;
+            while !in_tree[cur] {
+                let cell = (cur % self.width, cur / self.width);
+                // Only a lone cell has no neighbours, and it starts in the tree
+                let Some(&(nx, ny)) = self.grid_neighbors(cell).choose(&mut rng) else {
+                    return;
+                };
+                next[cur] = ny * self.width + nx;
+                cur = next[cur];
+            }
+
+            let mut cur = start;
+            while !in_tree[cur] {
+                in_tree[cur] = true;
+                let n = next[cur];
+                self.carve(
+                    (cur % self.width, cur / self.width),
+                    (n % self.width, n / self.width),
+                );
+                visited += 1;
+                cur = n;
+            }
+            progress(visited, total);
+        }
+    }
+
+    /// Aldous-Broder: a plain random walk that opens the wall each time it
+    /// first enters an unvisited cell. Uniform like Wilson's, but the walk
+    /// races through the early cells and crawls hunting for the last ones.
+    ///
+    /// Returns the number of steps the walk took.
+    pub fn generate_aldous_broder(&mut self) -> usize {
+        let mut rng = self.seeded_rng();
+        let mut visited = vec![vec![false; self.width]; self.height];
+        let mut cur = (
+            rng.random_range(0..self.width),
+            rng.random_range(0..self.height),
+        );
+        visited[cur.1][cur.0] = true;
+        let mut remaining = self.width * self.height - 1;
+        let mut steps = 0;
+
+        while remaining > 0 {
+            let Some(&next) = self.grid_neighbors(cur).choose(&mut rng) else {
+                break;
+            };
+            if !visited[next.1][next.0] {
+                self.carve(cur, next);
+                visited[next.1][next.0] = true;
+                remaining -= 1;
+            }
+            cur = next;
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Sidewinder: the top row is one open corridor; every other row is split
+    /// into runs that each open exactly one passage north.
+    ///
+    /// `bias` (0.0–1.0) is the chance of extending a run east instead of
+    /// closing it, so higher values give longer horizontal corridors.
+    pub fn generate_sidewinder(&mut self, bias: f64) {
+        let mut rng = self.seeded_rng();
+        let bias = bias.clamp(0.0, 1.0);
+
+        for x in 1..self.width {
+            self.vert_walls[0][x] = false;
+        }
+        for y in 1..self.height {
+            let mut run_start = 0;
+            for x in 0..self.width {
+                let at_east_edge = x + 1 == self.width;
+                if !at_east_edge && rng.random_bool(bias) {
+                    self.vert_walls[y][x + 1] = false;
+                } else {
+                    let member = rng.random_range(run_start..=x);
+                    self.hor_walls[y][member] = false;
+                    run_start = x + 1;
+                }
+            }
+        }
+    }
+
+    /// Binary tree: every cell opens one wall, picked at random between the
+    /// bias's vertical and horizontal direction. Cells on the biased edges
+    /// have only one option, and the biased corner opens nothing.
+    pub fn generate_binary_tree(&mut self, bias: BinaryTreeBias) {
+        let mut rng = self.seeded_rng();
+        let (north, east) = match bias {
+            BinaryTreeBias::NorthEast => (true, true),
+            BinaryTreeBias::NorthWest => (true, false),
+            BinaryTreeBias::SouthEast => (false, true),
+            BinaryTreeBias::SouthWest => (false, false),
+        };
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let vertical = match north {
+                    true if y > 0 => Some((x, y - 1)),
+                    false if y + 1 < self.height => Some((x, y + 1)),
+                    _ => None,
+                };
+                let horizontal = match east {
+                    true if x + 1 < self.width => Some((x + 1, y)),
+                    false if x > 0 => Some((x - 1, y)),
+                    _ => None,
+                };
+                let target = match (vertical, horizontal) {
+                    (Some(v), Some(h)) => Some(if rng.random_bool(0.5) { v } else { h }),
+                    (v, h) => v.or(h),
+                };
+                if let Some(next) = target {
+                    self.carve((x, y), next);
+                }
+            }
+        }
+    }
+
+    /// Hunt-and-kill: random-walk until boxed in, then scan row by row for the
+    /// first unvisited cell bordering the visited region and walk on from it.
+    /// The scan order keeps the result deterministic for a given seed.
+    pub fn generate_hunt_and_kill(&mut self) {
+        let mut rng = self.seeded_rng();
+        let mut visited = vec![vec![false; self.width]; self.height];
+        let mut cur = (
+            rng.random_range(0..self.width),
+            rng.random_range(0..self.height),
+        );
+        visited[cur.1][cur.0] = true;
+
+        loop {
+            // Kill: walk while there are unvisited neighbours
+            let unvisited: Vec<_> = self
+                .grid_neighbors(cur)
+                .into_iter()
+                .filter(|&(nx, ny)| !visited[ny][nx])
+                .collect();
+            if let Some(&next) = unvisited.choose(&mut rng) {
+                self.carve(cur, next);
+                visited[next.1][next.0] = true;
+                cur = next;
+                continue;
+            }
+
+            // Hunt: first unvisited cell adjacent to the visited region
+            let mut found = None;
+            'hunt: for y in 0..self.height {
+                for x in 0..self.width {
+                    if visited[y][x] {
+                        continue;
+                    }
+                    let adjacent: Vec<_> = self
+                        .grid_neighbors((x, y))
+                        .into_iter()
+                        .filter(|&(nx, ny)| visited[ny][nx])
+                        .collect();
+                    if let Some(&link) = adjacent.choose(&mut rng) {
+                        found = Some(((x, y), link));
+                        break 'hunt;
+                    }
+                }
+            }
+            match found {
+                Some((cell, link)) => {
+                    self.carve(cell, link);
+                    visited[cell.1][cell.0] = true;
+                    cur = cell;
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Recursive division: clear every interior wall, then split regions in
+    /// two with a wall that has a single gap, until regions are one cell
+    /// thin. Regions are cut across their longer side (randomly when square),
+    /// which gives long straight lanes.
+    pub fn generate_recursive_division(&mut self) {
+        let mut rng = self.seeded_rng();
+        for y in 0..self.height {
+            for x in 1..self.width {
+                self.vert_walls[y][x] = false;
+            }
+        }
+        for y in 1..self.height {
+            for x in 0..self.width {
+                self.hor_walls[y][x] = false;
+            }
+        }
+
+        // Explicit stack of (x, y, w, h) regions instead of recursion so thin
+        // strips on huge grids cannot overflow the call stack
+        let mut regions = vec![(0, 0, self.width, self.height)];
+        while let Some((x, y, w, h)) = regions.pop() {
+            if w < 2 || h < 2 {
+                continue;
+            }
+            let horizontal = match w.cmp(&h) {
+                Ordering::Less => true,
+                Ordering::Greater => false,
+                Ordering::Equal => rng.random_bool(0.5),
+            };
+            if horizontal {
+                let wall = y + rng.random_range(1..h);
+                let gap = x + rng.random_range(0..w);
+                for cx in x..x + w {
+                    self.hor_walls[wall][cx] = cx != gap;
+                }
+                regions.push((x, y, w, wall - y));
+                regions.push((x, wall, w, y + h - wall));
+            } else {
+                let wall = x + rng.random_range(1..w);
+                let gap = y + rng.random_range(0..h);
+                for cy in y..y + h {
+                    self.vert_walls[cy][wall] = cy != gap;
+                }
+                regions.push((x, y, wall - x, h));
+                regions.push((wall, y, x + w - wall, h));
+            }
+        }
+    }
+
+    /// Eller's algorithm: builds the maze one row at a time, tracking only
+    /// which set each cell of the current row belongs to. Within a row,
+    /// neighbouring cells from different sets are randomly joined; each set
+    /// then opens at least one passage down, and the last row joins every
+    /// remaining set.
+    ///
+    /// Auxiliary space is O(width): only the current row's set ids and a
+    /// per-row grouping are kept, regardless of the maze height. This makes
+    /// it the generator of choice for very tall mazes (1000×1000 and up).
+    pub fn generate_eller(&mut self) {
+        let mut rng = self.seeded_rng();
+        // 0 marks a cell that has not been given a set yet
+        let mut sets = vec![0usize; self.width];
+        let mut next_id = 1;
+
+        for y in 0..self.height {
+            let last_row = y + 1 == self.height;
+            for id in sets.iter_mut().filter(|id| **id == 0) {
+                *id = next_id;
+                next_id += 1;
+            }
+
+            // Join horizontally
+            for x in 0..self.width.saturating_sub(1) {
+                if sets[x] != sets[x + 1] && (last_row || rng.random_bool(0.5)) {
+                    self.vert_walls[y][x + 1] = false;
+                    let (keep, gone) = (sets[x], sets[x + 1]);
+                    for id in sets.iter_mut().filter(|id| **id == gone) {
+                        *id = keep;
+                    }
+                }
+            }
+            if last_row {
+                break;
+            }
+
+            // Group the row's cells by set, in order of first appearance so
+            // the result only depends on the seed
+            let mut group_of = BTreeMap::new();
+            let mut groups: Vec<Vec<usize>> = Vec::new();
+            for (x, &id) in sets.iter().enumerate() {
+                let g = *group_of.entry(id).or_insert_with(|| {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                });
+                groups[g].push(x);
+            }
+
+            // Every set carries on into the next row through at least one cell
+            let mut below = vec![0usize; self.width];
+            for members in &groups {
+                let forced = members[rng.random_range(0..members.len())];
+                for &x in members {
+                    if x == forced || rng.random_bool(0.5) {
+                        self.hor_walls[y + 1][x] = false;
+                        below[x] = sets[x];
+                    }
+                }
+            }
+            sets = below;
+        }
+    }
+
+    /// Braid the maze: knock down about `fraction` (0.0–1.0) of the standing
+    /// interior walls, chosen at random, so the maze gains loops. `0.0`
+    /// leaves it untouched and `1.0` opens every interior wall. On a
+    /// toroidal maze the wrap-around walls count as interior too. Without
+    /// an explicit `rng_seed` the maze's own seed is used, keeping seeded
+    /// runs reproducible.
+    pub fn add_loops(&mut self, fraction: f64, rng_seed: Option<u64>) {
+        let mut rng = StdRng::seed_from_u64(rng_seed.unwrap_or(self.seed));
+        let mut walls = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if x + 1 < self.width && self.vert_walls[y][x + 1] {
+                    walls.push(((x, y), (x + 1, y)));
+                }
+                if y + 1 < self.height && self.hor_walls[y + 1][x] {
+                    walls.push(((x, y), (x, y + 1)));
+                }
+            }
+        }
+        if self.wraps_x() {
+            let last = self.width - 1;
+            walls.extend(
+                (0..self.height)
+                    .filter(|&y| self.vert_walls[y][0])
+                    .map(|y| ((last, y), (0, y))),
+            );
+        }
+        if self.wraps_y() {
+            let last = self.height - 1;
+            walls.extend(
+                (0..self.width)
+                    .filter(|&x| self.hor_walls[0][x])
+                    .map(|x| ((x, last), (x, 0))),
+            );
+        }
+        // `+ 0.5` rounds to nearest without `f64::round`, which needs std
+        let count = (fraction.clamp(0.0, 1.0) * walls.len() as f64 + 0.5) as usize;
+        let (chosen, _) = walls.partial_shuffle(&mut rng, count);
+        for &(a, b) in chosen.iter() {
+            self.carve(a, b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DisjointSet;
+    use crate::Maze;
+
+    fn seeded(width: usize, height: usize, seed: u64) -> Maze {
+        let mut maze = Maze::new(width, height).unwrap();
+        maze.seed = seed;
+        maze
+    }
+
+    #[test]
+    fn disjoint_set_union_reports_new_joins() {
+        let mut sets = DisjointSet::new(4);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(!sets.union(1, 0));
+        assert!(sets.union(1, 3));
+        assert_eq!(sets.find(0), sets.find(2));
+    }
+
+    #[test]
+    fn kruskal_builds_a_spanning_tree() {
+        for seed in 0..20 {
+            let mut maze = seeded(12, 9, seed);
+            maze.generate_kruskal();
+            assert!(maze.is_connected(), "seed {seed}");
+            assert_eq!(maze.passage_count(), 12 * 9 - 1, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn aldous_broder_reaches_every_cell_once() {
+        for seed in 0..20 {
+            let mut maze = seeded(10, 7, seed);
+            let steps = maze.generate_aldous_broder();
+            assert!(maze.is_perfect(), "seed {seed}");
+            assert!(maze.unreachable_cells().is_empty(), "seed {seed}");
+            assert!(steps >= 10 * 7 - 1, "seed {seed}");
+        }
+    }
+
+    /// Mean moves from `start` to `end` over `runs` seeds of a 10x10 maze
+    /// carved by `carve`
+    fn mean_solution_length(runs: u64, carve: impl Fn(&mut Maze)) -> f64 {
+        let total: usize = (0..runs)
+            .map(|seed| {
+                let mut maze = seeded(10, 10, seed);
+                carve(&mut maze);
+                maze.solve().unwrap().len() - 1
+            })
+            .sum();
+        total as f64 / runs as f64
+    }
+
+    #[test]
+    fn aldous_broder_path_lengths_match_a_uniform_spanning_tree() {
+        // Wilson's algorithm also samples spanning trees uniformly, so the
+        // two means agree; depth-first mazes wind far longer
+        let aldous_broder = mean_solution_length(300, |m| {
+            m.generate_aldous_broder();
+        });
+        let wilson = mean_solution_length(300, Maze::generate_wilson);
+        let dfs = mean_solution_length(300, Maze::generate);
+        // About 24 moves for both samplers and 45 for depth-first
+        assert!(
+            (aldous_broder - wilson).abs() < 0.1 * wilson,
+            "Aldous-Broder {aldous_broder}, Wilson {wilson}"
+        );
+        assert!(
+            dfs > 1.5 * aldous_broder,
+            "DFS {dfs}, Aldous-Broder {aldous_broder}"
+        );
+    }
+
+    #[test]
+    fn add_loops_opens_wrap_around_walls() {
+        let mut maze = seeded(6, 5, 4);
+        maze.generate_toroidal();
+        maze.add_loops(1.0, None);
+        assert_eq!(maze.wall_count(), 0);
+        assert!((0..5).all(|y| !maze.vert_walls[y][0] && !maze.vert_walls[y][6]));
+        assert!((0..6).all(|x| !maze.hor_walls[0][x] && !maze.hor_walls[5][x]));
+    }
+}
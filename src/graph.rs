@@ -0,0 +1,247 @@
+// src/graph.rs
+
+use crate::{Maze, MazeError};
+use alloc::{collections::BTreeSet, format, string::String, vec, vec::Vec};
+
+// Graph views of the passages. Cells are numbered row by row: cell (x, y)
+// has index `y * width + x`.
+impl Maze {
+    /// For each cell index, the sorted indices of the cells reachable in one
+    /// step through an open passage
+    pub fn to_adjacency_list(&self) -> Vec<Vec<usize>> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|cell| {
+                let mut out: Vec<usize> = self
+                    .open_neighbors(cell)
+                    .into_iter()
+                    .map(|(x, y)| y * self.width + x)
+                    .collect();
+                out.sort_unstable();
+                out.dedup();
+                out
+            })
+            .collect()
+    }
+
+    /// Every open passage once, as `(a, b)` cell indices with `a < b`, in
+    /// ascending order. A perfect maze has `width * height - 1` edges.
+    pub fn to_edge_list(&self) -> Vec<(usize, usize)> {
+        self.to_adjacency_list()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(a, ns)| ns.into_iter().filter(move |&b| a < b).map(move |b| (a, b)))
+            .collect()
+    }
+
+    /// Cells whose removal would cut the passage graph in two, in row
+    /// order: the choke points every route between the parts must pass.
+    /// In a perfect maze that is every cell with more than one passage;
+    /// loops spare the cells on them. Found with an iterative Tarjan
+    /// depth-first search, so large mazes do not overflow the stack.
+    pub fn articulation_points(&self) -> Vec<(usize, usize)> {
+        let adjacency = self.to_adjacency_list();
+        let n = adjacency.len();
+        let mut discovered = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut cut = vec![false; n];
+        let mut time = 0;
+        for root in 0..n {
+            if discovered[root] != usize::MAX {
+                continue;
+            }
+            discovered[root] = time;
+            low[root] = time;
+            time += 1;
+            let mut root_children = 0;
+            // Each frame is a cell, its DFS parent and how many of its
+            // neighbours have been looked at
+            let mut stack = vec![(root, usize::MAX, 0)];
+            while let Some((cell, parent, next)) = stack.last_mut() {
+                let (cell, parent) = (*cell, *parent);
+                if let Some(&other) = adjacency[cell].get(*next) {
+                    *next += 1;
+                    if other == parent || other == cell {
+                        continue;
+                    }
+                    if discovered[other] == usize::MAX {
+                        discovered[other] = time;
+                        low[other] = time;
+                        time += 1;
+                        stack.push((other, cell, 0));
+                    } else {
+                        low[cell] = low[cell].min(discovered[other]);
+                    }
+                    continue;
+                }
+                stack.pop();
+                if parent == usize::MAX {
+                    continue;
+                }
+                low[parent] = low[parent].min(low[cell]);
+                if parent == root {
+                    root_children += 1;
+                } else if low[cell] >= discovered[parent] {
+                    cut[parent] = true;
+                }
+            }
+            cut[root] = root_children > 1;
+        }
+        (0..n)
+            .filter(|&i| cut[i])
+            .map(|i| (i % self.width, i / self.width))
+            .collect()
+    }
+
+    /// The passage graph as a Graphviz DOT `graph`: one node per cell,
+    /// labelled `(x,y)` and pinned at its grid position for `neato`, and one
+    /// edge per open passage. Edges on the start-to-end solution are drawn
+    /// red.
+    pub fn to_dot(&self) -> String {
+        let name = |i: usize| format!("c{}_{}", i % self.width, i / self.width);
+        let solution: BTreeSet<(usize, usize)> = self
+            .solve()
+            .unwrap_or_default()
+            .windows(2)
+            .map(|w| {
+                let (a, b) = (w[0].1 * self.width + w[0].0, w[1].1 * self.width + w[1].0);
+                (a.min(b), a.max(b))
+            })
+            .collect();
+
+        let mut dot = String::from("graph G {\n    node [shape=box];\n");
+        for i in 0..self.width * self.height {
+            let (x, y) = (i % self.width, i / self.width);
+            dot.push_str(&format!(
+                "    {} [label=\"({x},{y})\", pos=\"{x},-{y}!\"];\n",
+                name(i)
+            ));
+        }
+        for (a, b) in self.to_edge_list() {
+            let color = if solution.contains(&(a, b)) {
+                " [color=red]"
+            } else {
+                ""
+            };
+            dot.push_str(&format!("    {} -- {}{color};\n", name(a), name(b)));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Build a maze from neighbour lists in the `to_adjacency_list` layout.
+    /// An edge listed in either direction opens the wall between the two
+    /// cells; cells without an entry stay walled in. Fails with
+    /// `MazeError::NonAdjacentEdge` if an edge joins cells that are not
+    /// orthogonal neighbours or refers to a cell outside the grid.
+    pub fn from_adjacency_list(
+        adj: &[Vec<usize>],
+        (width, height): (usize, usize),
+    ) -> Result<Maze, MazeError> {
+        let mut maze = Maze::new(width, height)?;
+        let cells = width * height;
+        for (from, ns) in adj.iter().enumerate() {
+            for &to in ns {
+                let err = MazeError::NonAdjacentEdge { from, to };
+                if from >= cells || to >= cells {
+                    return Err(err);
+                }
+                let a = (from % width, from / width);
+                let b = (to % width, to / width);
+                if a.0.abs_diff(b.0) + a.1.abs_diff(b.1) != 1 {
+                    return Err(err);
+                }
+                maze.carve(a, b);
+            }
+        }
+        Ok(maze)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Maze;
+    use alloc::{collections::BTreeSet, vec, vec::Vec};
+
+    #[test]
+    fn edge_list_is_the_deduplicated_adjacency() {
+        for seed in 0..10 {
+            let mut maze = Maze::new(9, 6).unwrap();
+            maze.generate_with_seed(seed);
+            let adjacency = maze.to_adjacency_list();
+            let edges = maze.to_edge_list();
+
+            let from_adjacency: BTreeSet<(usize, usize)> = adjacency
+                .iter()
+                .enumerate()
+                .flat_map(|(a, ns)| ns.iter().map(move |&b| (a.min(b), a.max(b))))
+                .collect();
+            assert_eq!(edges, from_adjacency.into_iter().collect::<Vec<_>>());
+            assert!(edges.iter().all(|&(a, b)| adjacency[b].contains(&a)));
+            assert_eq!(edges.len(), 9 * 6 - 1);
+        }
+    }
+
+    /// Cells whose removal leaves the rest of a connected maze in more
+    /// than one piece, found by a flood fill around each cell in turn
+    fn cut_cells(maze: &Maze) -> Vec<(usize, usize)> {
+        let adjacency = maze.to_adjacency_list();
+        let n = adjacency.len();
+        (0..n)
+            .filter(|&removed| {
+                let from = if removed == 0 { 1 } else { 0 };
+                let mut seen = vec![false; n];
+                seen[removed] = true;
+                seen[from] = true;
+                let mut stack = vec![from];
+                let mut reached = 1;
+                while let Some(cell) = stack.pop() {
+                    for &next in &adjacency[cell] {
+                        if !seen[next] {
+                            seen[next] = true;
+                            reached += 1;
+                            stack.push(next);
+                        }
+                    }
+                }
+                reached < n - 1
+            })
+            .map(|i| (i % maze.width, i / maze.width))
+            .collect()
+    }
+
+    #[test]
+    fn perfect_maze_cuts_at_every_branching_cell() {
+        for seed in 0..10 {
+            let mut maze = Maze::new(9, 6).unwrap();
+            maze.generate_with_seed(seed);
+            let branching: Vec<_> = (0..6)
+                .flat_map(|y| (0..9).map(move |x| (x, y)))
+                .filter(|&cell| maze.open_neighbors(cell).len() > 1)
+                .collect();
+            assert_eq!(maze.articulation_points(), branching, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn braided_maze_matches_removing_each_cell() {
+        for seed in 0..10 {
+            let mut maze = Maze::new(9, 6).unwrap();
+            maze.generate_with_seed(seed);
+            maze.add_loops(0.2, None);
+            assert_eq!(maze.articulation_points(), cut_cells(&maze), "seed {seed}");
+        }
+        // A plain ring has no cut cells at all
+        let mut ring = Maze::new(2, 2).unwrap();
+        for (a, b) in [
+            ((0, 0), (1, 0)),
+            ((1, 0), (1, 1)),
+            ((1, 1), (0, 1)),
+            ((0, 1), (0, 0)),
+        ] {
+            ring.carve(a, b);
+        }
+        assert!(ring.articulation_points().is_empty());
+        assert!(cut_cells(&ring).is_empty());
+    }
+}
@@ -1,193 +1,454 @@
 // src/lib.rs
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{
+    collections::{BTreeSet, BinaryHeap},
+    vec,
+    vec::Vec,
+};
+use core::cmp::Reverse;
+#[cfg(feature = "std")]
 use image::{Rgb, RgbImage};
-use rand::{rng, seq::SliceRandom};
+#[cfg(feature = "std")]
+use map::textured;
+#[cfg(feature = "std")]
+use rand::rng;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use serde_json::json;
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashMap},
+
+mod analysis;
+#[cfg(feature = "std")]
+mod animate;
+mod builder;
+mod cells;
+#[cfg(feature = "std")]
+mod config;
+#[cfg(feature = "std")]
+mod draw;
+mod edit;
+mod error;
+mod export;
+mod fingerprint;
+mod fractal;
+mod generate;
+mod graph;
+mod locks;
+#[cfg(feature = "std")]
+mod map;
+mod mask;
+mod maze3d;
+#[cfg(feature = "std")]
+mod obj;
+mod one_way;
+#[cfg(feature = "std")]
+mod parallel;
+mod portal;
+mod rooms;
+mod smooth;
+mod solve;
+mod sparse;
+mod stl;
+#[cfg(feature = "std")]
+mod storage;
+mod transform;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod weights;
+
+pub use analysis::MazeStats;
+pub use builder::MazeBuilder;
+pub use cells::{CellInfo, WallInfo};
+#[cfg(feature = "std")]
+pub use config::{DrawTheme, MapTheme, MazeConfig};
+#[cfg(feature = "std")]
+pub use draw::{COORDINATE_MIN_CELL, DrawOptions, MarkerStyle};
+pub use edit::{EditOp, MazeEditor};
+pub use error::MazeError;
+pub use export::SvgLayer;
+pub use generate::{Algorithm, BinaryTreeBias};
+pub use locks::{Door, Key, MAX_KEYS, WallRef};
+#[cfg(feature = "std")]
+pub use map::{
+    KrunkerTheme, MapOptions, RAMP_OBJECT, TEXTURE_IDS, TRIGGER_OBJECT, WAYPOINT_SPACING,
 };
+#[cfg(feature = "std")]
+pub use mask::Mask;
+pub use maze3d::Maze3D;
+pub use portal::{PathStep, Portal};
+pub use rooms::Room;
+pub use solve::Solver;
+#[cfg(feature = "std")]
+pub use sparse::{AnyMaze, SPARSE_THRESHOLD, SparseMaze};
+pub use sparse::{MazeLike, WallSide};
+pub use stl::StlOptions;
+#[cfg(feature = "wasm")]
+pub use wasm::WasmMaze;
+pub use weights::WeightZone;
+
+/// A straight run of standing walls, in cell units (end exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Segment {
+    Vertical { x: usize, y1: usize, y2: usize },
+    Horizontal { y: usize, x1: usize, x2: usize },
+}
+
+/// Outcome of an interruptible search such as `Maze::solve_until`: the path,
+/// `None` when there is none, or `Err` with a partial path when stopped
+pub(crate) type Search = Result<Option<Vec<(usize, usize)>>, Vec<(usize, usize)>>;
 
 /// The core maze data (cells & walls) and all operations on it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Maze {
     pub width: usize,
     pub height: usize,
     pub vert_walls: Vec<Vec<bool>>,
     pub hor_walls: Vec<Vec<bool>>,
+    /// Seed feeding the generator RNG; reuse it to reproduce a layout
+    #[serde(default)]
+    pub seed: u64,
+    /// Entrance cell used by `solve`, the drawings and the map spawns
+    pub start: (usize, usize),
+    /// Exit cell used by `solve`, the drawings and the map spawns
+    pub end: (usize, usize),
+    /// Open areas placed by `carve_rooms`
+    #[serde(default)]
+    pub rooms: Vec<Room>,
+    /// Teleporters placed by `add_portal` and `add_portal_pairs`
+    #[serde(default)]
+    pub portals: Vec<Portal>,
+    /// Passages placed by `add_one_way`, as (from, to) pairs that may only
+    /// be walked from the first cell to the second
+    #[serde(default)]
+    pub one_way: BTreeSet<((usize, usize), (usize, usize))>,
+    /// Locked doors placed by `add_door`
+    #[serde(default)]
+    pub doors: Vec<Door>,
+    /// Keys for the doors, placed by `add_key`
+    #[serde(default)]
+    pub keys: Vec<Key>,
+    /// Cost of moving into each cell, indexed `[y][x]`; empty while every
+    /// cell costs 1 (see `set_cell_weight`)
+    #[serde(default)]
+    pub cell_weights: Vec<Vec<u32>>,
+    /// Subdivision depth of `generate_fractal`, which locates its split
+    /// lines for `DrawOptions::fractal_coloring`
+    #[serde(default)]
+    pub fractal_depth: Option<u32>,
+    /// Left/right and top/bottom edges wrap around (see `generate_toroidal`).
+    /// The wrap wall of a row is stored in both `vert_walls[y][0]` and
+    /// `vert_walls[y][width]`, and likewise for columns in `hor_walls`.
+    #[serde(default)]
+    pub toroidal: bool,
 }
 
 impl Maze {
-    pub fn new(width: usize, height: usize) -> Self {
+    /// A fully walled `width` x `height` grid with a random seed (0 without
+    /// the `std` feature), start in the top-left corner and end in the
+    /// bottom-right one. Fails with `MazeError::ZeroDimension` if either
+    /// side is zero.
+    pub fn new(width: usize, height: usize) -> Result<Self, MazeError> {
+        if width == 0 || height == 0 {
+            return Err(MazeError::ZeroDimension { width, height });
+        }
         let vert_walls = vec![vec![true; width + 1]; height];
         let hor_walls = vec![vec![true; width]; height + 1];
-        Maze {
+        Ok(Maze {
             width,
             height,
             vert_walls,
             hor_walls,
-        }
+            seed: random_seed(),
+            start: (0, 0),
+            end: (width - 1, height - 1),
+            rooms: Vec::new(),
+            portals: Vec::new(),
+            one_way: BTreeSet::new(),
+            doors: Vec::new(),
+            keys: Vec::new(),
+            cell_weights: Vec::new(),
+            fractal_depth: None,
+            toroidal: false,
+        })
+    }
+
+    /// Seeded RNG shared by the generators
+    fn seeded_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed)
+    }
+
+    /// Generate from `seed`, storing it in `self.seed`
+    pub fn generate_with_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.generate();
     }
 
     pub fn generate(&mut self) {
+        self.generate_with_progress(|_, _| {});
+    }
+
+    /// Same as `generate`, calling `progress(cells_visited, total_cells)`
+    /// every time the search enters a new cell
+    pub fn generate_with_progress(&mut self, mut progress: impl FnMut(usize, usize)) {
+        let mut rng = self.seeded_rng();
+        let total = self.width * self.height;
         let mut visited = vec![vec![false; self.width]; self.height];
-        let mut stack = Vec::new();
         visited[0][0] = true;
-        stack.push((0, 0, 0));
-
-        while let Some((x, y, dir_idx)) = stack.pop() {
-            let mut dirs = vec![
-                (1isize, 0isize, 'R'),
-                (-1, 0, 'L'),
-                (0, 1, 'D'),
-                (0, -1, 'U'),
-            ];
-            dirs.shuffle(&mut rng());
-
-            for i in dir_idx..dirs.len() {
-                let (dx, dy, dir) = dirs[i];
-                let nx = x as isize + dx;
-                let ny = y as isize + dy;
-                if nx >= 0 && nx < self.width as isize && ny >= 0 && ny < self.height as isize {
-                    let (nx, ny) = (nx as usize, ny as usize);
-                    if !visited[ny][nx] {
-                        match dir {
-                            'R' => self.vert_walls[y][x + 1] = false,
-                            'L' => self.vert_walls[y][x] = false,
-                            'D' => self.hor_walls[y + 1][x] = false,
-                            'U' => self.hor_walls[y][x] = false,
-                            _ => {}
-                        }
-                        stack.push((x, y, i + 1));
-                        visited[ny][nx] = true;
-                        stack.push((nx, ny, 0));
-                        break;
-                    }
+        let mut count = 1;
+        progress(count, total);
+        self.backtrack_from((0, 0), &mut visited, &mut rng, &mut || {
+            count += 1;
+            progress(count, total);
+        });
+    }
+
+    /// Depth-first backtracking from `start` (already marked visited) into
+    /// every unvisited cell reachable through unvisited cells, calling
+    /// `on_visit` for each
+    pub(crate) fn backtrack_from(
+        &mut self,
+        start: (usize, usize),
+        visited: &mut [Vec<bool>],
+        rng: &mut impl Rng,
+        on_visit: &mut impl FnMut(),
+    ) {
+        let mut stack = vec![start];
+        while let Some(&cell) = stack.last() {
+            let unvisited: Vec<_> = self
+                .grid_neighbors(cell)
+                .into_iter()
+                .filter(|&(nx, ny)| !visited[ny][nx])
+                .collect();
+            match unvisited.choose(rng) {
+                Some(&next) => {
+                    self.carve(cell, next);
+                    visited[next.1][next.0] = true;
+                    on_visit();
+                    stack.push(next);
+                }
+                None => {
+                    stack.pop();
                 }
             }
         }
     }
 
-    /// Solve via A* from top‑left to bottom‑right
-    pub fn solve(&self) -> Vec<(usize, usize)> {
+    /// Solve via A* from `self.start` to `self.end` (top‑left to bottom‑right
+    /// unless changed). Fails with `MazeError::OutOfBounds` when either cell
+    /// lies outside the grid and `MazeError::Unreachable` when there is no
+    /// path between them.
+    pub fn solve(&self) -> Result<Vec<(usize, usize)>, MazeError> {
+        for &(x, y) in [&self.start, &self.end] {
+            if !self.in_bounds((x, y)) {
+                return Err(MazeError::OutOfBounds { x, y });
+            }
+        }
+        self.solve_between(self.start, self.end)
+            .ok_or(MazeError::Unreachable {
+                from: self.start,
+                to: self.end,
+            })
+    }
+
+    /// Solve via A* between two arbitrary cells; `None` when either cell is
+    /// out of bounds or `end` cannot be reached from `start`. Portals are
+    /// taken as free moves, so the path may jump between their ends, and
+    /// one-way passages are only walked forwards. Stepping into a cell
+    /// costs its `cell_weight`, so the path is the cheapest rather than
+    /// the shortest once weights are set. With locked doors the
+    /// search runs over (cell, keys held) states, so the path may double
+    /// back to fetch keys.
+    pub fn solve_between(
+        &self,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> Option<Vec<(usize, usize)>> {
+        self.solve_until(start, end, || false).ok().flatten()
+    }
+
+    /// `solve_between`, calling `stop` before each A* iteration and giving
+    /// up as soon as it returns true. `Err` then holds the path to the
+    /// explored cell closest to `end` by the heuristic.
+    pub(crate) fn solve_until(
+        &self,
+        start: (usize, usize),
+        end: (usize, usize),
+        mut stop: impl FnMut() -> bool,
+    ) -> Search {
+        if !self.in_bounds(start) || !self.in_bounds(end) {
+            return Ok(None);
+        }
+        if !self.doors.is_empty() {
+            return self.solve_locked(start, end, stop);
+        }
         let total = self.width * self.height;
-        let start = 0;
-        let goal = total - 1;
+        let goal = end.1 * self.width + end.0;
+        let start = start.1 * self.width + start.0;
 
         let mut g_score = vec![usize::MAX; total];
-        let mut came_from = HashMap::new();
+        let mut came_from = vec![None; total];
         let mut open = BinaryHeap::new();
 
-        // Heuristic: Manhattan to goal
-        let h = |idx: usize| {
-            let x = (idx % self.width) as isize;
-            let y = (idx / self.width) as isize;
-            let gx = (self.width - 1) as isize;
-            let gy = (self.height - 1) as isize;
-            ((gx - x).abs() + (gy - y).abs()) as usize
-        };
+        let bound = self.distance_bound(end);
+        let h = |idx: usize| bound((idx % self.width, idx / self.width));
 
         g_score[start] = 0;
         open.push((Reverse(h(start)), start));
+        let mut closest = (h(start), start);
+        let mut stopped = false;
 
         while let Some((_, current)) = open.pop() {
             if current == goal {
                 break;
             }
-            let cx = current % self.width;
-            let cy = current / self.width;
-
-            let neighbors = [
-                (cx.wrapping_sub(1), cy, cx > 0 && !self.vert_walls[cy][cx]),
-                (
-                    cx + 1,
-                    cy,
-                    cx + 1 < self.width && !self.vert_walls[cy][cx + 1],
-                ),
-                (cx, cy.wrapping_sub(1), cy > 0 && !self.hor_walls[cy][cx]),
-                (
-                    cx,
-                    cy + 1,
-                    cy + 1 < self.height && !self.hor_walls[cy + 1][cx],
-                ),
-            ];
-
-            for &(nx, ny, ok) in &neighbors {
-                if !ok || nx >= self.width || ny >= self.height {
-                    continue;
-                }
+            if stop() {
+                stopped = true;
+                break;
+            }
+            closest = closest.min((h(current), current));
+            let cell = (current % self.width, current / self.width);
+            for ((nx, ny), cost) in self.steps(cell) {
                 let neighbor = ny * self.width + nx;
-                let tentative = g_score[current] + 1;
+                let tentative = g_score[current] + cost;
                 if tentative < g_score[neighbor] {
                     g_score[neighbor] = tentative;
-                    came_from.insert(neighbor, current);
+                    came_from[neighbor] = Some(current);
                     open.push((Reverse(tentative + h(neighbor)), neighbor));
                 }
             }
         }
 
+        let last = match (stopped, g_score[goal]) {
+            (true, _) => closest.1,
+            (false, usize::MAX) => return Ok(None),
+            (false, _) => goal,
+        };
+
         // Reconstruct path
         let mut path = Vec::new();
-        let mut cur = goal;
-        while let Some(&p) = came_from.get(&cur) {
+        let mut cur = last;
+        while let Some(p) = came_from[cur] {
             path.push((cur % self.width, cur / self.width));
             cur = p;
         }
-        path.push((0, 0));
+        path.push((start % self.width, start / self.width));
         path.reverse();
-        path
+        if stopped { Err(path) } else { Ok(Some(path)) }
     }
 
-    /// Draw maze + solution into an RGB image
-    pub fn draw(&self, cell_size: usize, wall_thick: usize) -> RgbImage {
-        let img_w = (self.width * cell_size + wall_thick) as u32;
-        let img_h = (self.height * cell_size + wall_thick) as u32;
-        let mut img = RgbImage::new(img_w, img_h);
+    /// Moves the solvers may make from `cell` with their cost: a step
+    /// through an open passage costs the weight of the cell it enters and
+    /// a portal jump 0
+    pub(crate) fn steps(
+        &self,
+        cell: (usize, usize),
+    ) -> impl Iterator<Item = ((usize, usize), usize)> + '_ {
+        let walks = self
+            .moves(cell)
+            .into_iter()
+            .map(|n| (n, self.cell_weight(n.0, n.1) as usize));
+        walks.chain(self.portal_partners(cell).map(|n| (n, 0)))
+    }
+
+    /// A* heuristic towards `end`: the Manhattan distance, measured around
+    /// the torus when the edges wrap and scaled by the cheapest cell
+    /// weight so it never overestimates
+    pub(crate) fn distance_bound(
+        &self,
+        end: (usize, usize),
+    ) -> impl Fn((usize, usize)) -> usize + '_ {
+        let (wrap_x, wrap_y) = (self.wraps_x(), self.wraps_y());
+        let manhattan = move |(x, y): (usize, usize)| {
+            let dx = x.abs_diff(end.0);
+            let dy = y.abs_diff(end.1);
+            let dx = if wrap_x { dx.min(self.width - dx) } else { dx };
+            let dy = if wrap_y { dy.min(self.height - dy) } else { dy };
+            dx + dy
+        };
+        // A path leaving its last portal still has to walk from that
+        // portal's far end, so the nearest portal end bounds it from below
+        let via_portal = self
+            .portals
+            .iter()
+            .flat_map(|p| [p.cell_a, p.cell_b])
+            .map(manhattan)
+            .min()
+            .unwrap_or(usize::MAX);
+        let cheapest = self.weight_range().map_or(1, |(lo, _)| lo as usize);
+        move |cell| manhattan(cell).min(via_portal) * cheapest
+    }
+}
 
-        // Colors
-        let white = Rgb([255, 255, 255]);
-        let black = Rgb([0, 0, 0]);
-        let red = Rgb([255, 0, 0]);
+// Raster drawing
+#[cfg(feature = "std")]
+impl Maze {
+    /// Draw maze + solution into an RGB image
+    pub fn draw(&self, opts: &DrawOptions) -> RgbImage {
+        self.draw_with_solution(opts, &self.drawn_solution(opts))
+    }
 
-        // Fill background
-        for x in 0..img_w {
-            for y in 0..img_h {
-                img.put_pixel(x, y, white);
-            }
+    /// `draw` with `solution` painted as the solution, e.g. one found by
+    /// another `Solver` or cut short by `solve_with_timeout`
+    pub fn draw_with_solution(&self, opts: &DrawOptions, solution: &[(usize, usize)]) -> RgbImage {
+        let cell_size = opts.cell_size;
+        let mut img = self.draw_no_solution(opts);
+        if opts.show_longest_path {
+            let longest = self.longest_path();
+            self.draw_path(&mut img, &longest, cell_size, draw::LONGEST_PATH_COLOR);
         }
-
-        // Walls
-        for y in 0..self.height {
-            let y0 = (y * cell_size) as u32;
-            for x in 0..=self.width {
-                if self.vert_walls[y][x] {
-                    let x0 = (x * cell_size) as u32;
-                    for dx in 0..wall_thick as u32 {
-                        for dy in 0..cell_size as u32 {
-                            img.put_pixel(x0 + dx, y0 + dy, black);
-                        }
-                    }
-                }
-            }
+        self.draw_solution(&mut img, solution, opts);
+        if opts.show_arrows {
+            self.draw_arrows(&mut img, solution, cell_size, draw::ARROW_COLOR);
         }
-        for y in 0..=self.height {
-            let y0 = (y * cell_size) as u32;
-            for x in 0..self.width {
-                if self.hor_walls[y][x] {
-                    let x0 = (x * cell_size) as u32;
-                    for dx in 0..cell_size as u32 {
-                        for dy in 0..wall_thick as u32 {
-                            img.put_pixel(x0 + dx, y0 + dy, black);
-                        }
-                    }
-                }
-            }
+        self.draw_one_way(&mut img, cell_size, draw::ONE_WAY_COLOR);
+        if opts.mark_dead_ends {
+            let dead_ends = self.dead_ends();
+            self.draw_markers(&mut img, &dead_ends, cell_size, draw::DEAD_END_COLOR);
+        }
+        if opts.mark_articulation_points {
+            let cuts = self.articulation_points();
+            self.draw_markers(&mut img, &cuts, cell_size, draw::ARTICULATION_POINT_COLOR);
+        }
+        self.draw_endpoints(&mut img, cell_size, opts);
+        if opts.show_coordinates {
+            self.draw_coordinates(&mut img, opts.cell_size, opts.wall);
         }
+        opts.pad(img)
+    }
+
+    /// Paint a path of adjacent cells onto an image produced by `draw*`.
+    /// Wrap-around steps of a toroidal maze are left undrawn.
+    pub fn draw_path(
+        &self,
+        img: &mut RgbImage,
+        path: &[(usize, usize)],
+        cell_size: usize,
+        color: Rgb<u8>,
+    ) {
+        self.paint_path(img, path, cell_size, |_| color);
+    }
 
-        // Solution path
+    /// `draw_path` with the colour of each step, numbered from 0, given by
+    /// `color`
+    pub(crate) fn paint_path(
+        &self,
+        img: &mut RgbImage,
+        path: &[(usize, usize)],
+        cell_size: usize,
+        color: impl Fn(usize) -> Rgb<u8>,
+    ) {
         let thickness = (cell_size as u32) / 2;
-        for window in self.solve().windows(2) {
+        for (step, window) in path.windows(2).enumerate() {
+            let color = color(step);
             let (x1, y1) = window[0];
             let (x2, y2) = window[1];
+            if x1.abs_diff(x2) + y1.abs_diff(y2) != 1 {
+                continue;
+            }
             let cx1 = x1 as u32 * cell_size as u32 + cell_size as u32 / 2;
             let cy1 = y1 as u32 * cell_size as u32 + cell_size as u32 / 2;
             let cx2 = x2 as u32 * cell_size as u32 + cell_size as u32 / 2;
@@ -195,32 +456,171 @@ impl Maze {
 
             if cx1 == cx2 {
                 let x0 = cx1.saturating_sub(thickness / 2);
-                let h = (cy2 as i32 - cy1 as i32).abs() as u32;
+                let h = cy2.abs_diff(cy1);
                 let y_min = cy1.min(cy2);
                 for dx in 0..thickness {
                     for dy in 0..=h {
-                        img.put_pixel(x0 + dx, y_min + dy, red);
+                        img.put_pixel(x0 + dx, y_min + dy, color);
                     }
                 }
             } else {
                 let y0 = cy1.saturating_sub(thickness / 2);
-                let w = (cx2 as i32 - cx1 as i32).abs() as u32;
+                let w = cx2.abs_diff(cx1);
                 let x_min = cx1.min(cx2);
                 for dy in 0..thickness {
                     for dx in 0..=w {
-                        img.put_pixel(x_min + dx, y0 + dy, red);
+                        img.put_pixel(x_min + dx, y0 + dy, color);
                     }
                 }
             }
         }
+    }
+
+    /// Paint a small centred square (a quarter of a cell wide) on each cell
+    pub fn draw_markers(
+        &self,
+        img: &mut RgbImage,
+        cells: &[(usize, usize)],
+        cell_size: usize,
+        color: Rgb<u8>,
+    ) {
+        let size = (cell_size as u32 / 4).max(1);
+        for &(x, y) in cells {
+            let x0 = (x * cell_size + cell_size / 2) as u32 - size / 2;
+            let y0 = (y * cell_size + cell_size / 2) as u32 - size / 2;
+            for dx in 0..size {
+                for dy in 0..size {
+                    img.put_pixel(x0 + dx, y0 + dy, color);
+                }
+            }
+        }
+    }
 
+    /// Draw only the walls into an RGB image, leaving the solution out. The
+    /// image is not padded, so that further layers line up with the cells;
+    /// finish with `DrawOptions::pad`.
+    pub fn draw_no_solution(&self, opts: &DrawOptions) -> RgbImage {
+        let (cell_size, wall_thick) = (opts.cell_size, opts.wall_thickness);
+        let img_w = (self.width * cell_size + wall_thick) as u32;
+        let img_h = (self.height * cell_size + wall_thick) as u32;
+        let mut img = RgbImage::from_pixel(img_w, img_h, opts.background);
+        self.paint_weights(&mut img, cell_size, opts.background, opts.weight_gradient);
+        self.paint_walls(&mut img, cell_size, wall_thick, opts.wall);
+        if opts.fractal_coloring {
+            self.paint_fractal_walls(&mut img, cell_size, wall_thick);
+        }
         img
     }
 
-    /// Build the JSON segments and full map structure
-    pub fn to_map_json(&self, cell_size: usize, wall_thick: usize) -> serde_json::Value {
+    /// Fill the weighted cells as `DrawOptions::weight_gradient` describes
+    pub(crate) fn paint_weights(
+        &self,
+        img: &mut RgbImage,
+        cell_size: usize,
+        background: Rgb<u8>,
+        gradient: (Rgb<u8>, Rgb<u8>),
+    ) {
+        let Some((_, hi)) = self.weight_range() else {
+            return;
+        };
+        for (y, row) in self.cell_weights.iter().enumerate() {
+            for (x, &weight) in row.iter().enumerate() {
+                let color = match weight {
+                    0 => gradient.0,
+                    1 => continue,
+                    _ => {
+                        let t = (weight - 1) as f64 / (hi - 1) as f64;
+                        draw::lerp_color(background, gradient.1, t)
+                    }
+                };
+                let (x0, y0) = ((x * cell_size) as u32, (y * cell_size) as u32);
+                for dy in 0..cell_size as u32 {
+                    for dx in 0..cell_size as u32 {
+                        img.put_pixel(x0 + dx, y0 + dy, color);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Repaint the standing walls on each `generate_fractal` split line in
+    /// the `draw::FRACTAL_COLORS` entry of its level
+    pub(crate) fn paint_fractal_walls(
+        &self,
+        img: &mut RgbImage,
+        cell_size: usize,
+        wall_thick: usize,
+    ) {
+        let colors = draw::FRACTAL_COLORS;
+        for (level, line) in self.fractal_lines() {
+            let color = colors[level as usize % colors.len()];
+            match line {
+                Segment::Vertical { x, y1, y2 } => {
+                    for y in (y1..y2).filter(|&y| self.vert_walls[y][x]) {
+                        let (x0, y0) = ((x * cell_size) as u32, (y * cell_size) as u32);
+                        for dx in 0..wall_thick as u32 {
+                            for dy in 0..cell_size as u32 {
+                                img.put_pixel(x0 + dx, y0 + dy, color);
+                            }
+                        }
+                    }
+                }
+                Segment::Horizontal { y, x1, x2 } => {
+                    for x in (x1..x2).filter(|&x| self.hor_walls[y][x]) {
+                        let (x0, y0) = ((x * cell_size) as u32, (y * cell_size) as u32);
+                        for dx in 0..cell_size as u32 {
+                            for dy in 0..wall_thick as u32 {
+                                img.put_pixel(x0 + dx, y0 + dy, color);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Paint every standing wall onto an image sized like `draw`'s output
+    pub(crate) fn paint_walls(
+        &self,
+        img: &mut RgbImage,
+        cell_size: usize,
+        wall_thick: usize,
+        color: Rgb<u8>,
+    ) {
+        for y in 0..self.height {
+            let y0 = (y * cell_size) as u32;
+            for x in 0..=self.width {
+                if self.vert_walls[y][x] {
+                    let x0 = (x * cell_size) as u32;
+                    for dx in 0..wall_thick as u32 {
+                        for dy in 0..cell_size as u32 {
+                            img.put_pixel(x0 + dx, y0 + dy, color);
+                        }
+                    }
+                }
+            }
+        }
+        for y in 0..=self.height {
+            let y0 = (y * cell_size) as u32;
+            for x in 0..self.width {
+                if self.hor_walls[y][x] {
+                    let x0 = (x * cell_size) as u32;
+                    for dx in 0..cell_size as u32 {
+                        for dy in 0..wall_thick as u32 {
+                            img.put_pixel(x0 + dx, y0 + dy, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Maze {
+    /// Merge standing walls into maximal straight runs: vertical runs first
+    /// (column by column), then horizontal ones (row by row)
+    pub(crate) fn wall_segments(&self) -> Vec<Segment> {
         let mut segments = Vec::new();
-        // vertical
         for x in 0..=self.width {
             let mut y = 0;
             while y < self.height {
@@ -230,14 +630,13 @@ impl Maze {
                     while y2 < self.height && self.vert_walls[y2][x] {
                         y2 += 1;
                     }
-                    segments.push(json!({"type":"vertical","x":x,"y1":y1,"y2":y2}));
+                    segments.push(Segment::Vertical { x, y1, y2 });
                     y = y2;
                 } else {
                     y += 1;
                 }
             }
         }
-        // horizontal
         for y in 0..=self.height {
             let mut x = 0;
             while x < self.width {
@@ -247,51 +646,185 @@ impl Maze {
                     while x2 < self.width && self.hor_walls[y][x2] {
                         x2 += 1;
                     }
-                    segments.push(json!({"type":"horizontal","y":y,"x1":x1,"x2":x2}));
+                    segments.push(Segment::Horizontal { y, x1, x2 });
                     x = x2;
                 } else {
                     x += 1;
                 }
             }
         }
+        segments
+    }
+}
+
+// Krunker map export
+#[cfg(feature = "std")]
+impl Maze {
+    /// Build the JSON segments and full map structure. Walls stand
+    /// `opts.wall_height` units tall on the floor; with a ceiling a slab the
+    /// size of the floor is laid over the maze (see `MapOptions`). Each
+    /// portal end becomes a `TRIGGER_OBJECT` zone on its cell and each
+    /// one-way passage a `RAMP_OBJECT` leading through it. Locked doors
+    /// become walls and keys `TRIGGER_OBJECT` zones, both carrying their
+    /// `key` ID for a Krunker script to match up.
+    pub fn to_map_json(&self, opts: &MapOptions) -> serde_json::Value {
+        self.to_map_json_with_theme(opts, &KrunkerTheme::default())
+    }
+
+    /// `to_map_json` with the lighting, sky and fog of `theme`
+    pub fn to_map_json_with_theme(
+        &self,
+        opts: &MapOptions,
+        theme: &KrunkerTheme,
+    ) -> serde_json::Value {
+        let (cell_size, wall_thick) = (opts.cell_size, opts.wall_thickness);
+        let wall_height = opts.wall_height as i32;
+        let segments = self.map_segments(opts);
         let fw = (self.width * cell_size) as i32;
         let fd = (self.height * cell_size) as i32;
         let mut sizes = vec![fw, 1, fd];
-        let mut objects = vec![json!({ "p":[fw/2, -1, fd/2], "si":0 })];
+        let mut objects = vec![textured(
+            json!({ "p":[fw/2, -1, fd/2], "si":0 }),
+            opts.floor_texture,
+        )];
 
         for (i, seg) in segments.iter().enumerate() {
             let si = i + 1;
-            if seg["type"] == "vertical" {
-                let x = seg["x"].as_i64().unwrap() as i32 * cell_size as i32;
-                let y1 = seg["y1"].as_i64().unwrap() as i32 * cell_size as i32;
-                let y2 = seg["y2"].as_i64().unwrap() as i32 * cell_size as i32;
-                let length = y2 - y1;
-                sizes.extend([wall_thick as i32, 20, length]);
-                objects.push(json!({"p":[x,0,(y1+y2)/2],"si":si}));
-            } else {
-                let y = seg["y"].as_i64().unwrap() as i32 * cell_size as i32;
-                let x1 = seg["x1"].as_i64().unwrap() as i32 * cell_size as i32;
-                let x2 = seg["x2"].as_i64().unwrap() as i32 * cell_size as i32;
-                let length = x2 - x1;
-                sizes.extend([length, 20, wall_thick as i32]);
-                objects.push(json!({"p":[(x1+x2)/2,0,y],"si":si}));
+            match *seg {
+                Segment::Vertical { x, y1, y2 } => {
+                    let x = x as i32 * cell_size as i32;
+                    let y1 = y1 as i32 * cell_size as i32;
+                    let y2 = y2 as i32 * cell_size as i32;
+                    let length = y2 - y1;
+                    sizes.extend([wall_thick as i32, wall_height, length]);
+                    objects.push(textured(
+                        json!({"p":[x,0,(y1+y2)/2],"si":si}),
+                        opts.wall_texture,
+                    ));
+                }
+                Segment::Horizontal { y, x1, x2 } => {
+                    let y = y as i32 * cell_size as i32;
+                    let x1 = x1 as i32 * cell_size as i32;
+                    let x2 = x2 as i32 * cell_size as i32;
+                    let length = x2 - x1;
+                    sizes.extend([length, wall_height, wall_thick as i32]);
+                    objects.push(textured(
+                        json!({"p":[(x1+x2)/2,0,y],"si":si}),
+                        opts.wall_texture,
+                    ));
+                }
             }
         }
 
-        let half = (cell_size as i32) / 2;
-        let start_spawn = json!([half, 0, half, 0, 0, 0]);
-        let end_spawn = json!([(fw - half), 0, (fd - half), 0, 0, 0]);
+        // Each room becomes one floor pad so arenas can be picked and
+        // retextured as a single piece in the editor
+        for room in &self.rooms {
+            let si = sizes.len() / 3;
+            let w = (room.width * cell_size) as i32;
+            let d = (room.height * cell_size) as i32;
+            let x = (room.x * cell_size) as i32 + w / 2;
+            let z = (room.y * cell_size) as i32 + d / 2;
+            sizes.extend([w, 1, d]);
+            objects.push(textured(
+                json!({"p":[x, -1, z],"si":si}),
+                opts.floor_texture,
+            ));
+        }
+
+        if opts.add_doors {
+            for (size, p) in self.map_doors(opts) {
+                let si = sizes.len() / 3;
+                sizes.extend(size);
+                objects.push(textured(json!({ "p": p, "si": si }), opts.wall_texture));
+            }
+        }
+
+        if opts.mark_solution_path {
+            for (size, p) in self.map_waypoints(opts) {
+                let si = sizes.len() / 3;
+                sizes.extend(size);
+                objects.push(textured(json!({ "p": p, "si": si }), opts.waypoint_texture));
+            }
+        }
+
+        for (size, p, d) in self.map_one_way(opts) {
+            let si = sizes.len() / 3;
+            sizes.extend(size);
+            objects.push(textured(
+                json!({ "i": map::RAMP_OBJECT, "p": p, "si": si, "d": d }),
+                opts.floor_texture,
+            ));
+        }
+
+        for (size, p, key) in self.map_locked_doors(opts) {
+            let si = sizes.len() / 3;
+            sizes.extend(size);
+            objects.push(textured(
+                json!({ "p": p, "si": si, "key": key }),
+                opts.wall_texture,
+            ));
+        }
+
+        for (size, p, key) in self.map_keys(opts) {
+            let si = sizes.len() / 3;
+            sizes.extend(size);
+            objects.push(json!({ "i": map::TRIGGER_OBJECT, "p": p, "si": si, "key": key }));
+        }
+
+        for (size, p) in self.map_portals(opts) {
+            let si = sizes.len() / 3;
+            sizes.extend(size);
+            objects.push(json!({ "i": map::TRIGGER_OBJECT, "p": p, "si": si }));
+        }
+
+        if let Some(height) = opts.ceiling() {
+            let si = sizes.len() / 3;
+            sizes.extend([fw, 1, fd]);
+            objects.push(textured(
+                json!({"p":[fw/2, height as i32, fd/2],"si":si}),
+                opts.ceiling_texture.unwrap_or(0),
+            ));
+        }
 
         json!({
             "name":    "GeneratedMaze",
-            "ambient": "#97a0a8",
-            "light":   "#f2f8fc",
-            "sky":     "#dce8ed",
-            "fog":     "#8d9aa0",
-            "fogD":    2000,
+            "ambient": theme.ambient,
+            "light":   theme.light,
+            "sky":     theme.sky,
+            "fog":     theme.fog,
+            "fogD":    theme.fog_distance,
             "xyz":     sizes,
             "objects": objects,
-            "spawns":  [start_spawn, end_spawn],
+            "spawns":  self.map_spawns(opts),
         })
     }
+
+    /// The wall runs of the map: `wall_segments` with the `add_doors` gaps
+    /// opened and without the outer walls unless `add_outer_walls`
+    pub(crate) fn map_segments(&self, opts: &MapOptions) -> Vec<Segment> {
+        let mut segments = if opts.add_doors {
+            let mut open = self.clone();
+            for ((x, y), side) in self.door_cells() {
+                open.set_wall(x, y, side, false);
+            }
+            open.wall_segments()
+        } else {
+            self.wall_segments()
+        };
+        if !opts.add_outer_walls {
+            segments.retain(|seg| match *seg {
+                Segment::Vertical { x, .. } => x != 0 && x != self.width,
+                Segment::Horizontal { y, .. } => y != 0 && y != self.height,
+            });
+        }
+        segments
+    }
+}
+
+/// Seed for a new maze: random with `std`, fixed without an entropy source
+fn random_seed() -> u64 {
+    #[cfg(feature = "std")]
+    return rng().random();
+    #[cfg(not(feature = "std"))]
+    0
 }
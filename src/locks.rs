@@ -0,0 +1,306 @@
+// src/locks.rs
+
+use crate::{Maze, MazeError, Search, WallSide};
+use alloc::{
+    collections::{BTreeMap, BinaryHeap},
+    vec::Vec,
+};
+use core::cmp::Reverse;
+use serde::{Deserialize, Serialize};
+
+/// Distinct key IDs a maze can use: the solver tracks the keys it holds
+/// as the bits of a `u8`
+pub const MAX_KEYS: u8 = 8;
+
+/// One wall of the grid, named from a cell next to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WallRef {
+    pub x: usize,
+    pub y: usize,
+    pub side: WallSide,
+}
+
+/// A locked door in an open passage: only a path holding the key with the
+/// same `key_id` may pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Door {
+    pub wall: WallRef,
+    pub key_id: u8,
+}
+
+/// A key lying on a cell, picked up by walking onto it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Key {
+    pub cell: (usize, usize),
+    pub key_id: u8,
+}
+
+impl Maze {
+    /// The two cells a wall separates, wrapping on a toroidal maze; `None`
+    /// for a wall on the outer edge or off the grid
+    pub(crate) fn wall_cells(&self, wall: WallRef) -> Option<((usize, usize), (usize, usize))> {
+        let cell = (wall.x, wall.y);
+        if !self.in_bounds(cell) {
+            return None;
+        }
+        let dir = match wall.side {
+            WallSide::North => 'U',
+            WallSide::South => 'D',
+            WallSide::East => 'R',
+            WallSide::West => 'L',
+        };
+        self.step(cell, dir).map(|other| (cell, other))
+    }
+
+    /// The wall between neighbouring cells `a` and `b`, named from `a`
+    pub(crate) fn wall_between(&self, a: (usize, usize), b: (usize, usize)) -> Option<WallRef> {
+        [
+            ('U', WallSide::North),
+            ('D', WallSide::South),
+            ('R', WallSide::East),
+            ('L', WallSide::West),
+        ]
+        .into_iter()
+        .find(|&(dir, _)| self.step(a, dir) == Some(b))
+        .map(|(_, side)| WallRef {
+            x: a.0,
+            y: a.1,
+            side,
+        })
+    }
+
+    /// Put a door locked with key `key_id` in `wall`, opening the passage
+    /// behind it if a wall stood there. Fails with `MazeError::OutOfBounds`
+    /// when the wall is not between two cells of the grid and
+    /// `MazeError::InvalidKeyId` when `key_id` is `MAX_KEYS` or more.
+    pub fn add_door(&mut self, wall: WallRef, key_id: u8) -> Result<(), MazeError> {
+        if key_id >= MAX_KEYS {
+            return Err(MazeError::InvalidKeyId(key_id));
+        }
+        let (a, b) = self.wall_cells(wall).ok_or(MazeError::OutOfBounds {
+            x: wall.x,
+            y: wall.y,
+        })?;
+        self.carve(a, b);
+        self.doors.push(Door { wall, key_id });
+        Ok(())
+    }
+
+    /// Lay key `key_id` on `cell`. Fails with `MazeError::OutOfBounds` when
+    /// the cell is outside the grid and `MazeError::InvalidKeyId` when
+    /// `key_id` is `MAX_KEYS` or more.
+    pub fn add_key(&mut self, cell: (usize, usize), key_id: u8) -> Result<(), MazeError> {
+        if key_id >= MAX_KEYS {
+            return Err(MazeError::InvalidKeyId(key_id));
+        }
+        if !self.in_bounds(cell) {
+            return Err(MazeError::OutOfBounds {
+                x: cell.0,
+                y: cell.1,
+            });
+        }
+        self.keys.push(Key { cell, key_id });
+        Ok(())
+    }
+
+    /// `doors` and `keys` carried over to `out`, a transformed copy of
+    /// this maze, with `cell` mapping old cells to new ones. Doors with a
+    /// side that does not map are dropped, and so are such keys.
+    pub(crate) fn moved_locks(
+        &self,
+        out: &mut Maze,
+        cell: impl Fn((usize, usize)) -> Option<(usize, usize)>,
+    ) {
+        out.doors = self
+            .doors
+            .iter()
+            .filter_map(|door| {
+                let (a, b) = self.wall_cells(door.wall)?;
+                Some(Door {
+                    wall: out.wall_between(cell(a)?, cell(b)?)?,
+                    key_id: door.key_id,
+                })
+            })
+            .collect();
+        out.keys = self
+            .keys
+            .iter()
+            .filter_map(|key| {
+                Some(Key {
+                    cell: cell(key.cell)?,
+                    key_id: key.key_id,
+                })
+            })
+            .collect();
+    }
+
+    /// A* over (cell, keys held) states for `solve_until`: walking onto a
+    /// key picks it up, and a door can only be passed with its key
+    pub(crate) fn solve_locked(
+        &self,
+        start: (usize, usize),
+        end: (usize, usize),
+        mut stop: impl FnMut() -> bool,
+    ) -> Search {
+        // Key bits needed to cross each locked passage, in both directions
+        let mut locks = BTreeMap::new();
+        for door in &self.doors {
+            if let Some((a, b)) = self.wall_cells(door.wall) {
+                *locks.entry((a, b)).or_insert(0u8) |= 1 << door.key_id;
+                *locks.entry((b, a)).or_insert(0u8) |= 1 << door.key_id;
+            }
+        }
+        let keys_at = |cell: (usize, usize)| {
+            self.keys
+                .iter()
+                .filter(|k| k.cell == cell)
+                .fold(0u8, |held, k| held | 1 << k.key_id)
+        };
+        let h = self.distance_bound(end);
+
+        let first = (start, keys_at(start));
+        let mut g_score = BTreeMap::from([(first, 0)]);
+        let mut came_from = BTreeMap::new();
+        let mut open = BinaryHeap::from([(Reverse(h(start)), first)]);
+        let mut goal = None;
+        let mut closest = (h(start), first);
+        let mut stopped = false;
+
+        while let Some((_, state @ (cell, held))) = open.pop() {
+            if cell == end {
+                goal = Some(state);
+                break;
+            }
+            if stop() {
+                stopped = true;
+                break;
+            }
+            closest = closest.min((h(cell), state));
+            let g = g_score[&state];
+            for (next, cost) in self.steps(cell) {
+                if locks
+                    .get(&(cell, next))
+                    .is_some_and(|&need| need & held != need)
+                {
+                    continue;
+                }
+                let next_state = (next, held | keys_at(next));
+                let tentative = g + cost;
+                if g_score.get(&next_state).is_none_or(|&old| tentative < old) {
+                    g_score.insert(next_state, tentative);
+                    came_from.insert(next_state, state);
+                    open.push((Reverse(tentative + h(next)), next_state));
+                }
+            }
+        }
+
+        let mut state = match (stopped, goal) {
+            (true, _) => closest.1,
+            (false, None) => return Ok(None),
+            (false, Some(goal)) => goal,
+        };
+        let mut path = Vec::from([state.0]);
+        while let Some(&prev) = came_from.get(&state) {
+            path.push(prev.0);
+            state = prev;
+        }
+        path.reverse();
+        if stopped { Err(path) } else { Ok(Some(path)) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_KEYS, WallRef};
+    use crate::{Maze, MazeError, Solver, WallSide};
+    use alloc::vec;
+
+    /// A 4x2 grid: a corridor along the top row from the start (0, 0) to
+    /// the end (3, 0), locked with key 0 between (1, 0) and (2, 0), and a
+    /// dead end down to (1, 1)
+    fn locked_corridor() -> Maze {
+        let mut maze = Maze::new(4, 2).unwrap();
+        maze.carve((0, 0), (1, 0));
+        maze.carve((2, 0), (3, 0));
+        maze.carve((1, 0), (1, 1));
+        let door = WallRef {
+            x: 1,
+            y: 0,
+            side: WallSide::East,
+        };
+        maze.add_door(door, 0).unwrap();
+        maze.end = (3, 0);
+        maze
+    }
+
+    #[test]
+    fn door_without_its_key_is_unreachable() {
+        let mut maze = locked_corridor();
+        maze.add_key((1, 1), 1).unwrap();
+        assert_eq!(
+            maze.solve(),
+            Err(MazeError::Unreachable {
+                from: (0, 0),
+                to: (3, 0)
+            })
+        );
+        assert_eq!(maze.solve_with(Solver::Bfs), None);
+        assert_eq!(maze.solve_with(Solver::Dfs), None);
+    }
+
+    #[test]
+    fn key_on_the_start_opens_the_door() {
+        let mut maze = locked_corridor();
+        maze.add_key((0, 0), 0).unwrap();
+        let straight = vec![(0, 0), (1, 0), (2, 0), (3, 0)];
+        assert_eq!(maze.solve(), Ok(straight.clone()));
+        assert_eq!(maze.solve_with(Solver::Bfs), Some(straight.clone()));
+        assert_eq!(maze.solve_with(Solver::Dfs), Some(straight));
+    }
+
+    #[test]
+    fn path_doubles_back_for_the_key() {
+        let mut maze = locked_corridor();
+        maze.add_key((1, 1), 0).unwrap();
+        let fetch = vec![(0, 0), (1, 0), (1, 1), (1, 0), (2, 0), (3, 0)];
+        assert_eq!(maze.solve(), Ok(fetch.clone()));
+        assert_eq!(maze.solve_with(Solver::Bfs), Some(fetch));
+    }
+
+    #[test]
+    fn bad_doors_and_keys_are_rejected() {
+        let mut maze = Maze::new(4, 2).unwrap();
+        let inner = WallRef {
+            x: 1,
+            y: 0,
+            side: WallSide::South,
+        };
+        assert_eq!(
+            maze.add_door(inner, MAX_KEYS),
+            Err(MazeError::InvalidKeyId(MAX_KEYS))
+        );
+        let outer = WallRef {
+            x: 0,
+            y: 0,
+            side: WallSide::North,
+        };
+        assert_eq!(
+            maze.add_door(outer, 0),
+            Err(MazeError::OutOfBounds { x: 0, y: 0 })
+        );
+        let off_grid = WallRef { x: 9, ..inner };
+        assert_eq!(
+            maze.add_door(off_grid, 0),
+            Err(MazeError::OutOfBounds { x: 9, y: 0 })
+        );
+        assert_eq!(
+            maze.add_key((0, 0), MAX_KEYS),
+            Err(MazeError::InvalidKeyId(MAX_KEYS))
+        );
+        assert_eq!(
+            maze.add_key((4, 1), 0),
+            Err(MazeError::OutOfBounds { x: 4, y: 1 })
+        );
+        assert!(maze.doors.is_empty() && maze.keys.is_empty());
+    }
+}
@@ -1,48 +1,468 @@
-use clap::Parser;
-use krunker_maze_generator::Maze;
-use std::{error::Error, fs::File, io::Write, path::PathBuf};
+use clap::{Parser, error::ErrorKind};
+use indicatif::ProgressBar;
+use krunker_maze_generator::{
+    Algorithm, BinaryTreeBias, COORDINATE_MIN_CELL, Maze, MazeConfig, MazeError, Solver,
+    StlOptions, SvgLayer,
+};
+use notify::{EventKind, RecursiveMode, Watcher};
+use rayon::prelude::*;
+use std::{
+    error::Error,
+    ffi::OsString,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_override_self = true)]
 struct Args {
-    /// Maze width in cells
-    #[arg(short = 'W', long, default_value_t = 50)]
-    width: usize,
-    /// Maze height in cells
-    #[arg(short = 'H', long, default_value_t = 50)]
-    height: usize,
-    /// Pixel size of each cell
-    #[arg(short = 'S', long, default_value_t = 40)]
-    cell_size: usize,
-    /// Wall thickness in pixels
-    #[arg(short = 'T', long, default_value_t = 4)]
-    wall_thickness: usize,
+    /// Maze, drawing and map options, shared with `maze.toml`
+    #[command(flatten)]
+    options: MazeConfig,
     /// Output image file path
     #[arg(short, long, default_value = "maze.png")]
     image: PathBuf,
     /// Output map JSON file path
     #[arg(short = 'M', long, default_value = "map.json")]
     map: PathBuf,
+    /// Load a maze saved with --save-maze, or a .csv written by --csv,
+    /// instead of generating one
+    #[arg(long)]
+    load_maze: Option<PathBuf>,
+    /// Save the maze itself so it can be reloaded later
+    #[arg(long)]
+    save_maze: Option<PathBuf>,
+    /// Also write the maze as ASCII art [default path: maze.txt]
+    #[arg(short = 'a', long, num_args = 0..=1, default_missing_value = "maze.txt")]
+    ascii: Option<PathBuf>,
+    /// Also write one CSV row per cell for spreadsheet analysis [default path: maze.csv]
+    #[arg(long, num_args = 0..=1, default_missing_value = "maze.csv")]
+    csv: Option<PathBuf>,
+    /// Also write the passage graph as Graphviz DOT [default path: maze.dot]
+    #[arg(long, num_args = 0..=1, default_missing_value = "maze.dot")]
+    dot: Option<PathBuf>,
+    /// Also write the maze as SVG [default path: maze.svg]
+    #[arg(long, num_args = 0..=1, default_missing_value = "maze.svg")]
+    svg: Option<PathBuf>,
+    /// Round the corners of the --svg solution path
+    #[arg(long)]
+    smooth_path: bool,
+    /// Solver used for the drawn solution path
+    #[arg(long, value_enum, default_value_t = Solver::AStar)]
+    solver: Solver,
+    /// Give up on the A* solution after this many milliseconds, drawing the
+    /// best partial path instead
+    #[arg(long, default_value_t = 5000)]
+    solve_timeout_ms: u64,
+    /// Print maze statistics as JSON to stdout
+    #[arg(long)]
+    stats: bool,
+    /// Print the maze's 0.0–1.0 difficulty score to stdout
+    #[arg(long)]
+    difficulty: bool,
+    /// Check that the maze is perfect and report components, unreachable cells or a loop
+    #[arg(long)]
+    verify: bool,
+    /// Check the generated map JSON against the Krunker map schema and exit without writing files
+    #[arg(long)]
+    validate: bool,
+    /// Also write the JSON Schema of the map format [default path: map.schema.json]
+    #[arg(long, num_args = 0..=1, default_missing_value = "map.schema.json")]
+    map_schema: Option<PathBuf>,
+    /// Also write a distance heat map image [default path: maze_heatmap.png]
+    #[arg(long, num_args = 0..=1, default_missing_value = "maze_heatmap.png")]
+    heatmap: Option<PathBuf>,
+    /// Also write a black-and-white wall height map for terrain importers
+    /// [default path: maze_heightmap.png]
+    #[arg(long, num_args = 0..=1, default_missing_value = "maze_heightmap.png")]
+    heightmap: Option<PathBuf>,
+    /// Also write a PNG with a transparent background [default path: maze_rgba.png]
+    #[arg(long, num_args = 0..=1, default_missing_value = "maze_rgba.png")]
+    png_rgba: Option<PathBuf>,
+    /// Also write an animated GIF of the search solving the maze [default path: maze.gif]
+    #[arg(long, num_args = 0..=1, default_missing_value = "maze.gif")]
+    gif: Option<PathBuf>,
+    /// Milliseconds each --gif frame is shown
+    #[arg(long, default_value_t = 50)]
+    gif_delay: u32,
+    /// Also write the solution path as JSON for Krunker scripts [default path: solution.json]
+    #[arg(long, num_args = 0..=1, default_missing_value = "solution.json")]
+    solution_json: Option<PathBuf>,
+    /// Also write the map geometry as a Wavefront OBJ model [default path: maze.obj]
+    #[arg(long, num_args = 0..=1, default_missing_value = "maze.obj")]
+    obj: Option<PathBuf>,
+    /// Also write the materials of the --obj model [default path: maze.mtl]
+    #[arg(long, num_args = 0..=1, default_missing_value = "maze.mtl")]
+    mtl: Option<PathBuf>,
+    /// Also write a binary STL model for 3D printing [default path: maze.stl]
+    #[arg(long, num_args = 0..=1, default_missing_value = "maze.stl")]
+    stl: Option<PathBuf>,
+    /// Cell width in millimetres of the --stl model, shrunk to fit a 200mm bed
+    #[arg(long, default_value_t = 5.0)]
+    stl_cell_size: f64,
     /// Skip JSON map generation
     #[arg(long)]
     no_map: bool,
+    /// Always generate, never reading or writing the maze cache
+    #[arg(long)]
+    no_cache: bool,
+    /// Where generated mazes are cached [default: krunker-maze-generator in
+    /// the temporary directory]
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+    /// Generate N mazes in parallel, numbering the --image and --map files
+    /// (maze_0001.png, map_0001.json, …); seeds run from --seed (or 0)
+    /// upwards and no other outputs are written
+    #[arg(long, value_name = "N", conflicts_with = "load_maze")]
+    batch: Option<usize>,
+    /// Print the maze statistics, with its size and seed, as JSON and exit
+    /// without writing any files
+    #[arg(long, conflicts_with = "batch")]
+    stats_only: bool,
+    /// TOML file of option values, keyed by option name with underscores
+    /// (`cell_size = 24`); options given on the command line win [default:
+    /// maze.toml in the current directory, when there is one]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Keep running and regenerate every output whenever the --config file
+    /// changes
+    #[arg(long)]
+    watch: bool,
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
+    let cli: Vec<OsString> = std::env::args_os().collect();
+    let args = parse_args(&cli).unwrap_or_else(|e| e.exit());
+    if args.watch {
+        let config = args.config.ok_or(format!(
+            "--watch needs --config or a {CONFIG_FILE} to watch"
+        ))?;
+        return watch(&cli, &config);
+    }
+    run(&args).map(drop)
+}
+
+/// Config file read when `--config` is not given and it exists
+const CONFIG_FILE: &str = "maze.toml";
+
+/// `Args` from the command line `cli` (program name first), laid over the
+/// options in the config file when there is one; `config` is then always
+/// set to that file
+fn parse_args(cli: &[OsString]) -> Result<Args, clap::Error> {
+    let args = Args::try_parse_from(cli)?;
+    let Some(path) = args.config.clone().or_else(|| {
+        let default = PathBuf::from(CONFIG_FILE);
+        default.is_file().then_some(default)
+    }) else {
+        return Ok(args);
+    };
+    let mut layered = vec![cli[0].clone()];
+    layered.extend(config_args(&path)?);
+    layered.extend(cli[1..].iter().cloned());
+    let mut args = Args::try_parse_from(layered)?;
+    args.config = Some(path);
+    Ok(args)
+}
+
+/// The `--config` TOML file as command-line options: `key = value` becomes
+/// `--key=value` with underscores turned into dashes, `true` a bare `--key`
+/// and an array one option per item, while `false` is left out
+fn config_args(path: &Path) -> Result<Vec<OsString>, clap::Error> {
+    let invalid =
+        |kind, message: String| clap::Error::raw(kind, format!("{}: {message}\n", path.display()));
+    let text = std::fs::read_to_string(path).map_err(|e| invalid(ErrorKind::Io, e.to_string()))?;
+    let table: toml::Table = text
+        .parse()
+        .map_err(|e: toml::de::Error| invalid(ErrorKind::InvalidValue, e.message().to_owned()))?;
+    let mut out = Vec::new();
+    for (key, value) in &table {
+        if key == "config" {
+            let message = "a config file cannot name another with `config`".to_owned();
+            return Err(invalid(ErrorKind::ArgumentConflict, message));
+        }
+        let option = format!("--{}", key.replace('_', "-"));
+        let items = match value {
+            toml::Value::Array(items) => items.as_slice(),
+            value => std::slice::from_ref(value),
+        };
+        for item in items {
+            match item {
+                toml::Value::Boolean(true) => out.push(option.clone().into()),
+                toml::Value::Boolean(false) => {}
+                toml::Value::String(s) => out.push(format!("{option}={s}").into()),
+                toml::Value::Integer(n) => out.push(format!("{option}={n}").into()),
+                toml::Value::Float(f) => out.push(format!("{option}={f}").into()),
+                _ => {
+                    let message = format!("`{key}` must be a string, number, boolean or array");
+                    return Err(invalid(ErrorKind::InvalidValue, message));
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// `--watch`: run once, then again every time `config` changes, re-reading
+/// it and printing the time and the new maze's statistics. Failed runs are
+/// reported and the watch carries on.
+fn watch(cli: &[OsString], config: &Path) -> Result<(), Box<dyn Error>> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    // Editors often save by replacing the file, which ends a watch on the
+    // file itself, so watch its directory instead
+    let dir = config
+        .parent()
+        .filter(|d| !d.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    println!("Watching {} for changes (Ctrl+C to stop)", config.display());
+    loop {
+        match parse_args(cli) {
+            Ok(args) => match run(&args) {
+                Ok(Some(maze)) => {
+                    let stats = maze.statistics();
+                    println!(
+                        "[{}] Regenerated a {}x{} maze (seed {}): solution {} cells, \
+                         {} dead ends, difficulty {:.2}",
+                        clock(),
+                        maze.width,
+                        maze.height,
+                        maze.seed,
+                        stats.solution_length,
+                        stats.dead_end_count,
+                        stats.difficulty
+                    );
+                }
+                Ok(None) => println!("[{}] Regenerated the batch", clock()),
+                Err(e) => eprintln!("[{}] Error: {e}", clock()),
+            },
+            Err(e) => eprint!("[{}] {e}", clock()),
+        }
+        loop {
+            match rx.recv()? {
+                Ok(event)
+                    if !matches!(event.kind, EventKind::Access(_))
+                        && event
+                            .paths
+                            .iter()
+                            .any(|p| p.file_name() == config.file_name()) =>
+                {
+                    break;
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Watch error: {e}"),
+            }
+        }
+        // Let the save finish, then drop the other events it raised
+        std::thread::sleep(Duration::from_millis(100));
+        while rx.try_recv().is_ok() {}
+    }
+}
+
+/// The current UTC time of day as `HH:MM:SS UTC`
+fn clock() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+        % 86_400;
+    format!(
+        "{:02}:{:02}:{:02} UTC",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Everything one invocation does: the maze after writing each output, or
+/// `None` for `--batch`
+fn run(args: &Args) -> Result<Option<Maze>, Box<dyn Error>> {
+    if let Some(count) = args.batch {
+        batch(args, count)?;
+        return Ok(None);
+    }
+    let maze = prepare(args)?;
+    outputs(&maze, args)?;
+    Ok(Some(maze))
+}
 
-    println!("Generating maze {}x{}…", args.width, args.height);
-    let mut maze = Maze::new(args.width, args.height);
-    maze.generate();
+/// Load or generate the maze and apply the start, end, portals and weights
+/// from `args`
+fn prepare(args: &Args) -> Result<Maze, Box<dyn Error>> {
+    let mut maze = match &args.load_maze {
+        Some(path) => {
+            status(args, format_args!("Loading maze from {}…", path.display()));
+            if path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("csv"))
+            {
+                Maze::from_csv(&std::fs::read_to_string(path)?)?
+            } else {
+                Maze::load(path)?
+            }
+        }
+        None => match cache_key(args) {
+            Some((dir, seed)) => cached(args, &dir, seed)?,
+            None => generate(args)?,
+        },
+    };
+
+    args.options.apply(&mut maze)?;
+    Ok(maze)
+}
+
+/// Print the statistics, validate or write every file `args` asks for
+fn outputs(maze: &Maze, args: &Args) -> Result<(), Box<dyn Error>> {
+    if args.stats_only {
+        let mut stats = serde_json::to_value(maze.statistics())?;
+        stats["width"] = maze.width.into();
+        stats["height"] = maze.height.into();
+        stats["seed"] = maze.seed.into();
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    let (map_opts, theme) = (args.options.map_options(), args.options.krunker_theme());
+
+    if args.validate {
+        let map_json = maze.to_map_json_with_theme(&map_opts, &theme);
+        return match Maze::validate_map_json(&map_json) {
+            Ok(()) => {
+                println!("Map JSON is valid");
+                Ok(())
+            }
+            Err(errors) => {
+                for error in &errors {
+                    eprintln!("invalid map: {error}");
+                }
+                Err(format!("{} map validation error(s)", errors.len()).into())
+            }
+        };
+    }
+
+    if args.verify {
+        verify(maze);
+    }
+
+    if args.difficulty {
+        println!("Difficulty: {:.3}", maze.difficulty_score());
+    }
+
+    if args.stats {
+        println!("{}", serde_json::to_string_pretty(&maze.statistics())?);
+    }
+
+    if let Some(path) = &args.save_maze {
+        maze.save(path)?;
+        println!("Maze saved to {}", path.display());
+    }
 
     println!("Drawing maze to image ({})…", args.image.display());
-    let img = maze.draw(args.cell_size, args.wall_thickness);
-    img.save(&args.image)?;
+    let overlays = overlays(maze, args);
+    let draw_opts = args.options.draw_options();
+    if args.options.show_coordinates && args.options.cell_size < COORDINATE_MIN_CELL {
+        eprintln!(
+            "warning: --show-coordinates needs --cell-size {COORDINATE_MIN_CELL} or more; \
+             leaving them out"
+        );
+    }
+    maze.draw_with_solution(&draw_opts, &overlays.solution)
+        .save(&args.image)?;
     println!("Image saved to {}", args.image.display());
 
+    if let Some(path) = &args.heatmap {
+        maze.draw_heatmap(&draw_opts).save(path)?;
+        println!("Heat map saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.heightmap {
+        maze.to_heightmap(args.options.cell_size).save(path)?;
+        println!("Height map saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.png_rgba {
+        maze.draw_rgba(&draw_opts).save(path)?;
+        println!("Transparent image saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.gif {
+        maze.save_solve_gif(path, &draw_opts, args.gif_delay)?;
+        println!("Solve animation saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.svg {
+        let layers = [
+            SvgLayer::Path(&overlays.longest, "#0000ff"),
+            if args.smooth_path {
+                SvgLayer::SmoothPath(&overlays.solution, "#ff0000")
+            } else {
+                SvgLayer::Path(&overlays.solution, "#ff0000")
+            },
+            SvgLayer::Markers(&overlays.dead_ends, "#ffa500"),
+            SvgLayer::Markers(&overlays.articulation_points, "#ffff00"),
+        ];
+        let svg =
+            maze.to_svg_with_layers(args.options.cell_size, args.options.wall_thickness, &layers);
+        std::fs::write(path, svg)?;
+        println!("SVG saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.ascii {
+        std::fs::write(path, maze.to_ascii())?;
+        println!("ASCII maze saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.csv {
+        std::fs::write(path, maze.to_csv())?;
+        println!("CSV saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.dot {
+        std::fs::write(path, maze.to_dot())?;
+        println!("DOT graph saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.solution_json {
+        let json = serde_json::to_string_pretty(&maze.solution_to_json())?;
+        std::fs::write(path, json)?;
+        println!("Solution JSON saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.mtl {
+        std::fs::write(path, Maze::to_mtl_string())?;
+        println!("OBJ materials saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.obj {
+        let mtllib = args
+            .mtl
+            .as_deref()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str());
+        std::fs::write(path, maze.to_obj_string(&map_opts, mtllib))?;
+        println!("OBJ model saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.stl {
+        let opts = StlOptions {
+            cell_size: args.stl_cell_size,
+            ..Default::default()
+        };
+        std::fs::write(path, maze.to_stl_bytes(&opts))?;
+        println!("STL model saved to {}", path.display());
+    }
+
+    if let Some(path) = &args.map_schema {
+        let schema = serde_json::to_string_pretty(&Maze::krunker_map_schema())?;
+        std::fs::write(path, schema)?;
+        println!("Map schema saved to {}", path.display());
+    }
+
     if !args.no_map {
         println!("Generating JSON map to {}…", args.map.display());
-        let map_json = maze.to_map_json(args.cell_size, args.wall_thickness);
+        let map_json = maze.to_map_json_with_theme(&map_opts, &theme);
         let mut f = File::create(&args.map)?;
         write!(f, "{}", serde_json::to_string_pretty(&map_json)?)?;
         println!("Map JSON saved to {}", args.map.display());
@@ -50,3 +470,252 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Generate `count` mazes with consecutive seeds on the rayon thread pool,
+/// writing each one's PNG and map JSON under a numbered file name
+fn batch(args: &Args, count: usize) -> Result<(), Box<dyn Error>> {
+    let (builder, (width, height)) = args.options.builder()?;
+    let first_seed = args.options.seed.unwrap_or(0);
+    let (map_opts, theme) = (args.options.map_options(), args.options.krunker_theme());
+    let draw_opts = args.options.draw_options();
+    println!("Generating {count} mazes {width}x{height}…");
+    let started = Instant::now();
+    let bar = ProgressBar::new(count as u64);
+    (0..count)
+        .into_par_iter()
+        .try_for_each(|i| -> Result<(), String> {
+            let seed = first_seed.wrapping_add(i as u64);
+            let mut maze = builder
+                .clone()
+                .seed(seed)
+                .build()
+                .map_err(|e| format!("seed {seed}: {e}"))?;
+            args.options
+                .apply(&mut maze)
+                .map_err(|e| format!("seed {seed}: {e}"))?;
+            let overlays = overlays(&maze, args);
+            let image = numbered(&args.image, i + 1);
+            maze.draw_with_solution(&draw_opts, &overlays.solution)
+                .save(&image)
+                .map_err(|e| format!("{}: {e}", image.display()))?;
+            if !args.no_map {
+                let map = numbered(&args.map, i + 1);
+                let json = maze.to_map_json_with_theme(&map_opts, &theme);
+                serde_json::to_string_pretty(&json)
+                    .map_err(|e| e.to_string())
+                    .and_then(|json| std::fs::write(&map, json).map_err(|e| e.to_string()))
+                    .map_err(|e| format!("{}: {e}", map.display()))?;
+            }
+            bar.inc(1);
+            Ok(())
+        })?;
+    bar.finish_and_clear();
+    println!(
+        "Generated {count} mazes (seeds {first_seed}–{}) in {:.2?}: {} to {}",
+        first_seed.wrapping_add(count.saturating_sub(1) as u64),
+        started.elapsed(),
+        numbered(&args.image, 1).display(),
+        numbered(&args.image, count).display()
+    );
+    Ok(())
+}
+
+/// `path` with `_NNNN` appended to its file stem, e.g. `maze.png` becomes
+/// `maze_0001.png` for `n == 1`
+fn numbered(path: &Path, n: usize) -> PathBuf {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("maze");
+    let name = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{stem}_{n:04}.{ext}"),
+        None => format!("{stem}_{n:04}"),
+    };
+    path.with_file_name(name)
+}
+
+/// The solution path from `args.solver`. A* stops after
+/// `--solve-timeout-ms`, warning and returning its best partial path.
+fn solution(maze: &Maze, args: &Args) -> Vec<(usize, usize)> {
+    if args.solver != Solver::AStar {
+        return maze.solve_with(args.solver).unwrap_or_default();
+    }
+    match maze.solve_with_timeout(Duration::from_millis(args.solve_timeout_ms)) {
+        Ok(path) => path,
+        Err(MazeError::SolverTimeout { partial }) => {
+            eprintln!(
+                "Warning: solver timed out after {} ms, drawing a partial path",
+                args.solve_timeout_ms
+            );
+            partial
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Paths and cells drawn over the walls of the PNG and SVG
+struct Overlays {
+    solution: Vec<(usize, usize)>,
+    longest: Vec<(usize, usize)>,
+    dead_ends: Vec<(usize, usize)>,
+    articulation_points: Vec<(usize, usize)>,
+}
+
+/// The paths and markers to draw on top of the walls, each left empty when
+/// `args` turns it off
+fn overlays(maze: &Maze, args: &Args) -> Overlays {
+    let solution = if args.options.no_solution {
+        Vec::new()
+    } else {
+        solution(maze, args)
+    };
+    let longest = if args.options.show_longest_path {
+        maze.longest_path()
+    } else {
+        Vec::new()
+    };
+    let dead_ends = if args.options.mark_dead_ends {
+        maze.dead_ends()
+    } else {
+        Vec::new()
+    };
+    let articulation_points = if args.options.mark_articulation_points {
+        maze.articulation_points()
+    } else {
+        Vec::new()
+    };
+    Overlays {
+        solution,
+        longest,
+        dead_ends,
+        articulation_points,
+    }
+}
+
+/// Mazes with more cells than this (200x200) can take half a second or
+/// more to carve, so `generate` shows a progress bar for them
+const PROGRESS_CELLS: usize = 200 * 200;
+
+/// Cells carved between progress bar redraws
+const PROGRESS_STEP: usize = 1024;
+
+/// The cache directory and seed when `args` asks for a maze
+/// `Maze::load_cached` can supply: a given seed, caching left on, and no
+/// option outside the cache key that changes the layout
+fn cache_key(args: &Args) -> Option<(PathBuf, u64)> {
+    let plain = args.options.mask_image.is_none()
+        && !args.options.toroidal
+        && args.options.rooms == 0
+        && args.options.loop_fraction == 0.0
+        && args.options.threads <= 1
+        && args.options.min_difficulty.is_none()
+        && (args.options.algorithm != Algorithm::Sidewinder || args.options.sidewinder_bias == 0.5)
+        && (args.options.algorithm != Algorithm::BinaryTree
+            || args.options.binary_tree_bias == BinaryTreeBias::NorthEast);
+    if args.no_cache || args.stats_only || !plain {
+        return None;
+    }
+    let dir = args
+        .cache_dir
+        .clone()
+        .unwrap_or_else(|| std::env::temp_dir().join("krunker-maze-generator"));
+    Some((dir, args.options.seed?))
+}
+
+/// The maze from the cache in `dir`, generating and caching it on a miss
+fn cached(args: &Args, dir: &Path, seed: u64) -> Result<Maze, Box<dyn Error>> {
+    if let Some(maze) = Maze::load_cached(
+        dir,
+        args.options.width,
+        args.options.height,
+        seed,
+        args.options.algorithm,
+    ) {
+        status(
+            args,
+            format_args!(
+                "Loaded {}x{} maze from the cache (seed {seed})",
+                args.options.width, args.options.height
+            ),
+        );
+        return Ok(maze);
+    }
+    let maze = generate(args)?;
+    if let Err(e) = maze.save_cached(dir, args.options.algorithm) {
+        eprintln!(
+            "warning: could not cache the maze in {}: {e}",
+            dir.display()
+        );
+    }
+    Ok(maze)
+}
+
+/// Generate a fresh maze with the algorithm and parameters from `args`
+fn generate(args: &Args) -> Result<Maze, Box<dyn Error>> {
+    let (mut builder, (width, height)) = args.options.builder()?;
+    match &args.options.mask_image {
+        Some(path) => status(
+            args,
+            format_args!("Generating maze {width}x{height} from {}…", path.display()),
+        ),
+        None => status(args, format_args!("Generating maze {width}x{height}…")),
+    }
+    if let Some(seed) = args.options.seed {
+        builder = builder.seed(seed);
+    }
+    // Only the plain depth-first and Wilson's generators report progress
+    let reports = args.options.mask_image.is_none()
+        && !args.options.toroidal
+        && args.options.rooms == 0
+        && match args.options.algorithm {
+            Algorithm::Dfs => args.options.threads <= 1,
+            Algorithm::Wilson => true,
+            _ => false,
+        };
+    let cells = width * height;
+    let maze = if reports && cells > PROGRESS_CELLS {
+        let bar = ProgressBar::new(cells as u64);
+        let maze = builder.build_with_progress(|visited, total| {
+            if visited % PROGRESS_STEP == 0 || visited == total {
+                bar.set_position(visited as u64);
+            }
+        })?;
+        bar.finish_and_clear();
+        maze
+    } else {
+        builder.build()?
+    };
+    status(args, format_args!("Seed: {}", maze.seed));
+    Ok(maze)
+}
+
+/// Print a progress message, on stderr under --stats-only so that stdout
+/// holds nothing but the statistics JSON
+fn status(args: &Args, message: std::fmt::Arguments) {
+    if args.stats_only {
+        eprintln!("{message}");
+    } else {
+        println!("{message}");
+    }
+}
+
+/// Print whether the maze is perfect and, if not, why
+fn verify(maze: &Maze) {
+    if maze.is_perfect() {
+        println!("Verify: maze is perfect (1 connected component)");
+        return;
+    }
+    println!("Verify: maze is NOT perfect");
+    println!(
+        "  {} connected component(s)",
+        maze.connected_components().len()
+    );
+    let unreachable = maze.unreachable_cells();
+    if !unreachable.is_empty() {
+        println!(
+            "  {} cell(s) unreachable from (0, 0), e.g. {:?}",
+            unreachable.len(),
+            &unreachable[..unreachable.len().min(10)]
+        );
+    }
+    if let Some(cycle) = maze.find_cycle() {
+        println!("  loop of {} cells: {:?}", cycle.len(), cycle);
+    }
+}
@@ -0,0 +1,565 @@
+// src/map.rs
+
+use crate::{Maze, WallSide};
+use serde_json::{Value, json};
+use std::{
+    f64::consts::{FRAC_PI_2, PI},
+    ops::RangeInclusive,
+};
+
+/// Texture IDs offered by the Krunker editor's texture list; 0 is the
+/// default texture, which is what an object without a `ti` field gets
+pub const TEXTURE_IDS: RangeInclusive<u32> = 0..=31;
+
+/// Everything `Maze::to_map_json` needs to know about the Krunker map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapOptions {
+    /// Map units per maze cell
+    pub cell_size: usize,
+    /// Wall thickness in map units
+    pub wall_thickness: usize,
+    /// Height of the walls standing on the floor
+    pub wall_height: usize,
+    /// Lay a ceiling slab the size of the floor at this height
+    pub ceiling_height: Option<usize>,
+    /// Lay the ceiling slab even without a `ceiling_height`, just above the
+    /// walls at `wall_height + 1`
+    pub add_ceiling: bool,
+    /// Emit the walls around the edge of the maze; without them the map is
+    /// open to the sides
+    pub add_outer_walls: bool,
+    /// Put this many spawns on dead ends, spread evenly through them; 0
+    /// keeps the two spawns on `start` and `end`
+    pub spawn_count: usize,
+    /// Explicit spawn cells; when non-empty they replace `spawn_count`
+    pub spawn_cells: Vec<(usize, usize)>,
+    /// Texture ID (see `TEXTURE_IDS`) of the floor and room pads
+    pub floor_texture: u32,
+    /// Texture ID of the walls
+    pub wall_texture: u32,
+    /// Texture ID of the ceiling slab; `None` leaves it on the default
+    pub ceiling_texture: Option<u32>,
+    /// Open the outer wall north of cell (0, 0) and south of the
+    /// bottom-right cell and frame each gap with a door: two jambs and a
+    /// lintel in the wall texture
+    pub add_doors: bool,
+    /// Height of the door openings; the lintel fills the wall above, and
+    /// stands one wall thickness tall on doors as high as the walls
+    pub door_height: usize,
+    /// Stand a thin pillar on every `WAYPOINT_SPACING`th cell of the
+    /// solution so players can follow it to the exit
+    pub mark_solution_path: bool,
+    /// Texture ID of the waypoint pillars; keep it different from
+    /// `wall_texture` so they stand out
+    pub waypoint_texture: u32,
+}
+
+impl Default for MapOptions {
+    fn default() -> Self {
+        MapOptions {
+            cell_size: 40,
+            wall_thickness: 4,
+            wall_height: 20,
+            ceiling_height: None,
+            add_ceiling: false,
+            add_outer_walls: true,
+            spawn_count: 0,
+            spawn_cells: Vec::new(),
+            floor_texture: 0,
+            wall_texture: 0,
+            ceiling_texture: None,
+            add_doors: false,
+            door_height: 16,
+            mark_solution_path: false,
+            waypoint_texture: 1,
+        }
+    }
+}
+
+impl MapOptions {
+    /// Height of the ceiling slab, if there is one
+    pub(crate) fn ceiling(&self) -> Option<usize> {
+        match self.ceiling_height {
+            Some(height) => Some(height),
+            None => self.add_ceiling.then_some(self.wall_height + 1),
+        }
+    }
+}
+
+/// Map-wide lighting and atmosphere, as hex colours and a fog distance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KrunkerTheme {
+    pub ambient: String,
+    pub light: String,
+    pub sky: String,
+    pub fog: String,
+    /// Distance at which the fog becomes opaque (`fogD`)
+    pub fog_distance: u32,
+}
+
+impl Default for KrunkerTheme {
+    /// The neutral grey daylight the generator has always used
+    fn default() -> Self {
+        KrunkerTheme::new("#97a0a8", "#f2f8fc", "#dce8ed", "#8d9aa0", 2000)
+    }
+}
+
+impl KrunkerTheme {
+    fn new(ambient: &str, light: &str, sky: &str, fog: &str, fog_distance: u32) -> Self {
+        KrunkerTheme {
+            ambient: ambient.to_string(),
+            light: light.to_string(),
+            sky: sky.to_string(),
+            fog: fog.to_string(),
+            fog_distance,
+        }
+    }
+
+    /// Dim torch light with close, dark fog
+    pub fn dungeon() -> Self {
+        KrunkerTheme::new("#3a3230", "#b08a5a", "#120e0c", "#1a1412", 600)
+    }
+
+    /// Bright, cold light with white fog
+    pub fn snow() -> Self {
+        KrunkerTheme::new("#c8d4e0", "#ffffff", "#e8f0f8", "#f0f4f8", 900)
+    }
+
+    /// Warm sunlight and sandy haze
+    pub fn desert() -> Self {
+        KrunkerTheme::new("#b89a70", "#fff0d0", "#f2d9a8", "#d9b98a", 1500)
+    }
+}
+
+/// Tag a map object with a texture; the default texture 0 is left implicit
+pub(crate) fn textured(mut object: Value, texture: u32) -> Value {
+    if texture != 0 {
+        object["ti"] = json!(texture);
+    }
+    object
+}
+
+/// Solution cells between two waypoint pillars of
+/// `MapOptions::mark_solution_path`
+pub const WAYPOINT_SPACING: usize = 5;
+
+/// Object type (`i`) of a trigger zone in the Krunker editor, used for
+/// the portal ends; objects without an `i` are plain cubes
+pub const TRIGGER_OBJECT: u32 = 9;
+
+/// Object type (`i`) of a ramp in the Krunker editor, used for one-way
+/// passages; its `d` is the quarter turn it rises towards, clockwise from
+/// -z
+pub const RAMP_OBJECT: u32 = 7;
+
+/// Top-level keys every Krunker map document carries
+const MAP_KEYS: [&str; 9] = [
+    "name", "ambient", "light", "sky", "fog", "fogD", "xyz", "objects", "spawns",
+];
+
+impl Maze {
+    /// The solution for Krunker scripts, as
+    /// `{"path":[{"x":0,"z":0},...],"length":N}`: cells in order from
+    /// `start` to `end`, `x` being the column and `z` the row (Krunker keeps
+    /// `y` for height), and `length` the number of moves, as in
+    /// `MazeStats::solution_length`. Multiply by the map's cell size and add
+    /// half a cell to reach map units. The path is empty when the maze
+    /// cannot be solved.
+    pub fn solution_to_json(&self) -> Value {
+        let path = self.solve().unwrap_or_default();
+        json!({
+            "path": path.iter().map(|&(x, z)| json!({"x": x, "z": z})).collect::<Vec<_>>(),
+            "length": path.len().saturating_sub(1),
+        })
+    }
+
+    /// Check a map document such as `to_map_json` produces against the
+    /// shape Krunker expects: the top-level keys are present, `xyz` holds
+    /// whole `[w, h, d]` triples, every object's `si` names one of them and
+    /// every spawn has 6 entries. Returns every violation found, not just
+    /// the first.
+    pub fn validate_map_json(v: &Value) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        for key in MAP_KEYS {
+            if v.get(key).is_none() {
+                errors.push(format!("missing top-level key \"{key}\""));
+            }
+        }
+
+        let mut triples = 0;
+        match v.get("xyz") {
+            Some(Value::Array(xyz)) => {
+                if xyz.len() % 3 != 0 {
+                    errors.push(format!("xyz has {} values, not a multiple of 3", xyz.len()));
+                }
+                triples = xyz.len() / 3;
+            }
+            Some(_) => errors.push("xyz is not an array".to_string()),
+            None => {}
+        }
+
+        match v.get("objects") {
+            Some(Value::Array(objects)) => {
+                for (i, object) in objects.iter().enumerate() {
+                    match object.get("si").and_then(Value::as_u64) {
+                        Some(si) if (si as usize) < triples => {}
+                        Some(si) => errors.push(format!(
+                            "object {i} has si {si} but xyz holds {triples} sizes"
+                        )),
+                        None => errors.push(format!("object {i} has no integer si")),
+                    }
+                }
+            }
+            Some(_) => errors.push("objects is not an array".to_string()),
+            None => {}
+        }
+
+        match v.get("spawns") {
+            Some(Value::Array(spawns)) => {
+                for (i, spawn) in spawns.iter().enumerate() {
+                    let len = spawn.as_array().map(Vec::len);
+                    if len != Some(6) {
+                        errors.push(format!("spawn {i} is not an array of 6 values"));
+                    }
+                }
+            }
+            Some(_) => errors.push("spawns is not an array".to_string()),
+            None => {}
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// A JSON Schema (draft 2020-12) describing the documents `to_map_json`
+    /// produces, for checking map files with external tools before
+    /// uploading them. It covers the same ground as `validate_map_json`
+    /// except that it cannot tie an object's `si` to the length of `xyz`,
+    /// and it is stricter about value types and unknown keys.
+    pub fn krunker_map_schema() -> Value {
+        let colour = json!({ "type": "string", "pattern": "^#[0-9a-fA-F]{6}$" });
+        let numbers = |len: usize| {
+            json!({
+                "type": "array",
+                "items": { "type": "number" },
+                "minItems": len,
+                "maxItems": len,
+            })
+        };
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Krunker map",
+            "type": "object",
+            "required": MAP_KEYS,
+            "additionalProperties": false,
+            "properties": {
+                "name": { "type": "string" },
+                "ambient": colour,
+                "light": colour,
+                "sky": colour,
+                "fog": colour,
+                "fogD": { "type": "number", "minimum": 0 },
+                "xyz": {
+                    "description": "Object sizes as flattened [w, h, d] triples",
+                    "type": "array",
+                    "items": { "type": "number", "minimum": 0 },
+                },
+                "objects": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["p", "si"],
+                        "additionalProperties": false,
+                        "properties": {
+                            "p": numbers(3),
+                            "si": {
+                                "description": "Index of the object's triple in xyz",
+                                "type": "integer",
+                                "minimum": 0,
+                            },
+                            "i": {
+                                "description": "Object type; absent for cubes",
+                                "type": "integer",
+                                "minimum": 0,
+                            },
+                            "d": {
+                                "description": "Quarter turns of a ramp, clockwise from -z",
+                                "type": "integer",
+                                "minimum": 0,
+                                "maximum": 3,
+                            },
+                            "key": {
+                                "description": "Key ID joining a locked door to its key",
+                                "type": "integer",
+                                "minimum": 0,
+                                "maximum": crate::MAX_KEYS - 1,
+                            },
+                            "ti": {
+                                "type": "integer",
+                                "minimum": TEXTURE_IDS.start(),
+                                "maximum": TEXTURE_IDS.end(),
+                            },
+                        },
+                    },
+                },
+                "spawns": {
+                    "description": "[x, y, z, 0, yaw, 0] per spawn point",
+                    "type": "array",
+                    "items": numbers(6),
+                },
+            },
+        })
+    }
+
+    /// Boundary gaps framed by `MapOptions::add_doors`, as (cell, side)
+    pub(crate) fn door_cells(&self) -> [((usize, usize), WallSide); 2] {
+        [
+            ((0, 0), WallSide::North),
+            ((self.width - 1, self.height - 1), WallSide::South),
+        ]
+    }
+
+    /// The jambs and lintel of each door as `(size, position)` pairs, in
+    /// the units and bottom-centre positions of the other map objects
+    pub(crate) fn map_doors(&self, opts: &MapOptions) -> Vec<([i32; 3], [i32; 3])> {
+        let (cell, thick) = (opts.cell_size as i32, opts.wall_thickness as i32);
+        let door_h = opts.door_height as i32;
+        let lintel_h = (opts.wall_height as i32 - door_h).max(thick);
+        let depth = 2 * thick;
+        let mut pieces = Vec::new();
+        for ((x, y), side) in self.door_cells() {
+            let x0 = x as i32 * cell;
+            let z = match side {
+                WallSide::North => y as i32 * cell,
+                _ => (y as i32 + 1) * cell,
+            };
+            for jamb_x in [x0 + thick, x0 + cell - thick] {
+                pieces.push(([thick, door_h, depth], [jamb_x, 0, z]));
+            }
+            pieces.push(([cell, lintel_h, depth], [x0 + cell / 2, door_h, z]));
+        }
+        pieces
+    }
+
+    /// The `mark_solution_path` pillars as `(size, position)` pairs: one
+    /// unit square and `wall_height` tall in the centre of every
+    /// `WAYPOINT_SPACING`th solution cell, counting from the start
+    pub(crate) fn map_waypoints(&self, opts: &MapOptions) -> Vec<([i32; 3], [i32; 3])> {
+        let cell = opts.cell_size as i32;
+        let size = [1, opts.wall_height as i32, 1];
+        self.solve()
+            .unwrap_or_default()
+            .iter()
+            .step_by(WAYPOINT_SPACING)
+            .skip(1)
+            .map(|&(x, y)| {
+                (
+                    size,
+                    [x as i32 * cell + cell / 2, 0, y as i32 * cell + cell / 2],
+                )
+            })
+            .collect()
+    }
+
+    /// Trigger zones of the portals as `(size, position)` pairs, one for
+    /// each end in portal order: the cell inside the walls, `wall_height`
+    /// tall. Link each pair to teleport to the other in the editor.
+    pub(crate) fn map_portals(&self, opts: &MapOptions) -> Vec<([i32; 3], [i32; 3])> {
+        let cell = opts.cell_size as i32;
+        let inner = (cell - 2 * opts.wall_thickness as i32).max(1);
+        let size = [inner, opts.wall_height as i32, inner];
+        self.portals
+            .iter()
+            .flat_map(|p| [p.cell_a, p.cell_b])
+            .map(|(x, y)| {
+                (
+                    size,
+                    [x as i32 * cell + cell / 2, 0, y as i32 * cell + cell / 2],
+                )
+            })
+            .collect()
+    }
+
+    /// Ramps of the one-way passages as `(size, position, d)`: a narrow ramp
+    /// in the half of the `from` cell next to the gap, rising to half the
+    /// wall height towards `to`. Walking up it drops a player into `to`,
+    /// and from `to` the passage is a ledge too tall to climb.
+    pub(crate) fn map_one_way(&self, opts: &MapOptions) -> Vec<([i32; 3], [i32; 3], u8)> {
+        let cell = opts.cell_size as i32;
+        let inner = (cell - 2 * opts.wall_thickness as i32).max(1);
+        let rise = (opts.wall_height as i32 / 2).max(1);
+        self.one_way
+            .iter()
+            .filter_map(|&(from, to)| {
+                let (d, (dx, dz)) = [('U', (0, -1)), ('R', (1, 0)), ('D', (0, 1)), ('L', (-1, 0))]
+                    .into_iter()
+                    .enumerate()
+                    .find(|&(_, (dir, _))| self.step(from, dir) == Some(to))
+                    .map(|(d, (_, delta))| (d as u8, delta))?;
+                let size = if dx == 0 {
+                    [inner, rise, cell / 2]
+                } else {
+                    [cell / 2, rise, inner]
+                };
+                let x = from.0 as i32 * cell + cell / 2 + dx * cell / 4;
+                let z = from.1 as i32 * cell + cell / 2 + dz * cell / 4;
+                Some((size, [x, 0, z], d))
+            })
+            .collect()
+    }
+
+    /// Locked doors as `(size, position, key_id)`: a full-height wall
+    /// filling the passage, for a script to remove once the player holds
+    /// the key
+    pub(crate) fn map_locked_doors(&self, opts: &MapOptions) -> Vec<([i32; 3], [i32; 3], u8)> {
+        let cell = opts.cell_size as i32;
+        let (thick, height) = (opts.wall_thickness as i32, opts.wall_height as i32);
+        self.doors
+            .iter()
+            .map(|door| {
+                let (x, y, side) = door.wall.side.canonical(door.wall.x, door.wall.y);
+                let (x, y) = (x as i32 * cell, y as i32 * cell);
+                let piece = if side == WallSide::North {
+                    ([cell, height, thick], [x + cell / 2, 0, y])
+                } else {
+                    ([thick, height, cell], [x, 0, y + cell / 2])
+                };
+                (piece.0, piece.1, door.key_id)
+            })
+            .collect()
+    }
+
+    /// Keys as `(size, position, key_id)`: a trigger zone a quarter of a
+    /// cell across in the middle of the key's cell
+    pub(crate) fn map_keys(&self, opts: &MapOptions) -> Vec<([i32; 3], [i32; 3], u8)> {
+        let cell = opts.cell_size as i32;
+        let size = (cell / 4).max(1);
+        self.keys
+            .iter()
+            .map(|key| {
+                let (x, y) = (key.cell.0 as i32, key.cell.1 as i32);
+                (
+                    [size, size, size],
+                    [x * cell + cell / 2, 0, y * cell + cell / 2],
+                    key.key_id,
+                )
+            })
+            .collect()
+    }
+
+    /// Spawn entries for the map: `[x, y, z, 0, yaw, 0]` at cell centres.
+    /// Spawns picked from `spawn_count` or `spawn_cells` on a dead end get
+    /// a yaw (radians about the vertical axis, 0 facing -z) pointing down
+    /// its only passage, into the maze; the default start/end pair keeps 0.
+    pub(crate) fn map_spawns(&self, opts: &MapOptions) -> Vec<serde_json::Value> {
+        let cells = if !opts.spawn_cells.is_empty() {
+            opts.spawn_cells.clone()
+        } else if opts.spawn_count > 0 {
+            let dead_ends = self.dead_ends();
+            let count = opts.spawn_count.min(dead_ends.len());
+            (0..count)
+                .map(|i| dead_ends[i * dead_ends.len() / count])
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let facing = !cells.is_empty();
+        let cells = if facing {
+            cells
+        } else {
+            vec![self.start, self.end]
+        };
+
+        let (cs, half) = (opts.cell_size as i32, opts.cell_size as i32 / 2);
+        cells
+            .into_iter()
+            .map(|(x, y)| {
+                let neighbors = if facing && self.in_bounds((x, y)) {
+                    self.open_neighbors((x, y))
+                } else {
+                    Vec::new()
+                };
+                let yaw = match neighbors.as_slice() {
+                    &[n] => match ['R', 'L', 'D', 'U']
+                        .into_iter()
+                        .find(|&d| self.step((x, y), d) == Some(n))
+                    {
+                        Some('R') => json!(-FRAC_PI_2),
+                        Some('L') => json!(FRAC_PI_2),
+                        Some('D') => json!(PI),
+                        _ => json!(0),
+                    },
+                    _ => json!(0),
+                };
+                json!([x as i32 * cs + half, 0, y as i32 * cs + half, 0, yaw, 0])
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KrunkerTheme, MapOptions, Maze, WallRef, WallSide};
+
+    #[test]
+    fn map_json_matches_the_schema() {
+        let validator = jsonschema::validator_for(&Maze::krunker_map_schema()).unwrap();
+        let mut maze = Maze::new(7, 5).unwrap();
+        maze.generate_with_seed(4);
+        // Portals, one-way passages, locked doors and keys add the `i`,
+        // `d` and `key` object fields
+        maze.add_portal((0, 4), (6, 0)).unwrap();
+        maze.add_one_way((2, 2), (3, 2)).unwrap();
+        let door = WallRef {
+            x: 4,
+            y: 1,
+            side: WallSide::East,
+        };
+        maze.add_door(door, 2).unwrap();
+        maze.add_key((1, 3), 2).unwrap();
+        let plain = MapOptions::default();
+        let everything = MapOptions {
+            ceiling_height: Some(30),
+            spawn_count: 3,
+            ceiling_texture: Some(5),
+            add_doors: true,
+            mark_solution_path: true,
+            ..MapOptions::default()
+        };
+        let open = MapOptions {
+            add_ceiling: true,
+            add_outer_walls: false,
+            spawn_cells: vec![(1, 1), (6, 4)],
+            ..MapOptions::default()
+        };
+        let dungeon = KrunkerTheme::dungeon();
+        for (opts, theme) in [
+            (&plain, &KrunkerTheme::default()),
+            (&everything, &dungeon),
+            (&open, &KrunkerTheme::default()),
+        ] {
+            let map = maze.to_map_json_with_theme(opts, theme);
+            let errors: Vec<_> = validator.iter_errors(&map).map(|e| e.to_string()).collect();
+            assert!(errors.is_empty(), "{opts:?}: {errors:?}");
+            assert_eq!(Maze::validate_map_json(&map), Ok(()));
+            let objects = map["objects"].as_array().unwrap();
+            for field in ["i", "d", "key"] {
+                assert!(objects.iter().any(|o| o.get(field).is_some()), "{field}");
+            }
+        }
+    }
+
+    #[test]
+    fn schema_rejects_malformed_maps() {
+        let validator = jsonschema::validator_for(&Maze::krunker_map_schema()).unwrap();
+        let mut maze = Maze::new(3, 3).unwrap();
+        maze.generate_with_seed(1);
+        let mut map = maze.to_map_json(&MapOptions::default());
+        map["spawns"][0] = serde_json::json!([0, 0]);
+        assert!(!validator.is_valid(&map));
+        assert!(Maze::validate_map_json(&map).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+// src/mask.rs
+
+use crate::{Maze, MazeError};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use image::{GrayImage, ImageResult, Luma};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// Helpers for building the boolean grids taken by `Maze::generate_masked`
+#[cfg(feature = "std")]
+pub struct Mask;
+
+#[cfg(feature = "std")]
+impl Mask {
+    /// Read an image as a mask, one pixel per cell: pixels darker than
+    /// `threshold` (luma, 0–255) are inside the maze, lighter ones are left
+    /// out. Draw the shape in black on a white background.
+    pub fn from_image(path: &Path, threshold: u8) -> ImageResult<Vec<Vec<bool>>> {
+        let img = image::open(path)?.into_luma8();
+        Ok(img
+            .rows()
+            .map(|row| row.map(|px| px.0[0] < threshold).collect())
+            .collect())
+    }
+}
+
+impl Maze {
+    /// Recursive backtracker restricted to the cells where `mask[y][x]` is
+    /// true; every other cell (including any outside the mask's bounds)
+    /// stays fully walled. Each connected region of the mask becomes its own
+    /// perfect maze. `start` and `end` are moved to the first and last
+    /// masked cells in row-major order. Fails with `MazeError::InvalidMask`
+    /// unless the mask is exactly `height` rows of `width` cells with at
+    /// least one of them set.
+    pub fn generate_masked(&mut self, mask: &[Vec<bool>]) -> Result<(), MazeError> {
+        if mask.len() != self.height
+            || mask.iter().any(|row| row.len() != self.width)
+            || !mask.iter().flatten().any(|&c| c)
+        {
+            return Err(MazeError::InvalidMask);
+        }
+        let inside = |x: usize, y: usize| mask[y][x];
+        let mut rng = self.seeded_rng();
+        let mut visited: Vec<Vec<bool>> = (0..self.height)
+            .map(|y| (0..self.width).map(|x| !inside(x, y)).collect())
+            .collect();
+        let mut cells = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| inside(x, y));
+        if let Some(first) = cells.next() {
+            self.start = first;
+            self.end = cells.next_back().unwrap_or(first);
+        }
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !visited[y][x] {
+                    visited[y][x] = true;
+                    self.backtrack_from((x, y), &mut visited, &mut rng, &mut || {});
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Wall images
+#[cfg(feature = "std")]
+impl Maze {
+    /// Load a maze drawn as a black-and-white wall image: a
+    /// `2 * width + 1` by `2 * height + 1` pixel grid in which the pixel at
+    /// (`2x + 1`, `2y + 1`) is cell (x, y), the pixels between two cells
+    /// are the wall they share (black = wall, white = passage, split at
+    /// luma 128) and the outer ring holds the boundary walls. So every cell
+    /// is a 2×2 block with its west and north walls, plus one closing row
+    /// and column. Cell and corner pixels are ignored; `start` and `end`
+    /// keep their `Maze::new` defaults. Fails with
+    /// `MazeError::InvalidMaskImage` when a side is even or shorter than 3
+    /// pixels and `MazeError::ImageLoad` when the file cannot be decoded.
+    pub fn from_mask_image(path: &Path) -> Result<Maze, MazeError> {
+        let img = image::open(path)
+            .map_err(|e| MazeError::ImageLoad(e.to_string()))?
+            .into_luma8();
+        let (w, h) = img.dimensions();
+        if w < 3 || h < 3 || w % 2 == 0 || h % 2 == 0 {
+            return Err(MazeError::InvalidMaskImage {
+                width: w,
+                height: h,
+            });
+        }
+        let mut maze = Maze::new((w / 2) as usize, (h / 2) as usize)?;
+        let wall = |px: usize, py: usize| img.get_pixel(px as u32, py as u32).0[0] < 128;
+        for y in 0..maze.height {
+            for x in 0..=maze.width {
+                maze.vert_walls[y][x] = wall(2 * x, 2 * y + 1);
+            }
+        }
+        for y in 0..=maze.height {
+            for x in 0..maze.width {
+                maze.hor_walls[y][x] = wall(2 * x + 1, 2 * y);
+            }
+        }
+        Ok(maze)
+    }
+
+    /// Save the walls as the black-and-white image read by
+    /// `from_mask_image`, one pixel per cell, wall and corner post
+    pub fn save_mask_image(&self, path: &Path) -> ImageResult<()> {
+        let grid = self.wall_grid();
+        let img = GrayImage::from_fn(grid[0].len() as u32, grid.len() as u32, |px, py| {
+            Luma([if grid[py as usize][px as usize] {
+                0
+            } else {
+                255
+            }])
+        });
+        img.save(path)
+    }
+
+    /// The walls as `2 * height + 1` rows of `2 * width + 1` blocks, true
+    /// where a wall or corner post stands (the layout of
+    /// `from_mask_image`)
+    pub(crate) fn wall_grid(&self) -> Vec<Vec<bool>> {
+        let mut grid = alloc::vec![alloc::vec![false; 2 * self.width + 1]; 2 * self.height + 1];
+        for (gy, row) in grid.iter_mut().enumerate() {
+            for (gx, block) in row.iter_mut().enumerate() {
+                let (x, y) = (gx / 2, gy / 2);
+                *block = match (gx % 2, gy % 2) {
+                    (0, 0) => true,
+                    (0, _) => self.vert_walls[y][x],
+                    (_, 0) => self.hor_walls[y][x],
+                    _ => false,
+                };
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Maze, MazeError, MazeLike, WallSide};
+    use alloc::{vec, vec::Vec};
+
+    /// A 6x5 mask with a 2x2 hole and the bottom-right corner cut off
+    fn holed_mask() -> Vec<Vec<bool>> {
+        let mut mask = vec![vec![true; 6]; 5];
+        for (x, y) in [(2, 1), (3, 1), (2, 2), (3, 2), (5, 4)] {
+            mask[y][x] = false;
+        }
+        mask
+    }
+
+    #[test]
+    fn masked_out_cells_stay_walled() {
+        let mask = holed_mask();
+        for seed in 0..5 {
+            let mut maze = Maze::new(6, 5).unwrap();
+            maze.seed = seed;
+            maze.generate_masked(&mask).unwrap();
+            let sides = [
+                WallSide::North,
+                WallSide::South,
+                WallSide::East,
+                WallSide::West,
+            ];
+            for (x, y) in [(2, 1), (3, 1), (2, 2), (3, 2), (5, 4)] {
+                assert!(sides.iter().all(|&side| maze.has_wall(x, y, side)));
+            }
+            // The rest is one region, carved as a spanning tree
+            assert_eq!(maze.passage_count(), 6 * 5 - 5 - 1, "seed {seed}");
+            assert_eq!((maze.start, maze.end), ((0, 0), (4, 4)));
+            assert!(maze.solve().is_ok());
+        }
+    }
+
+    #[test]
+    fn misshapen_or_empty_masks_are_rejected() {
+        let mut maze = Maze::new(6, 5).unwrap();
+        let mut short_row = holed_mask();
+        short_row[3].pop();
+        let mut extra_row = holed_mask();
+        extra_row.push(vec![true; 6]);
+        for mask in [short_row, extra_row, vec![vec![false; 6]; 5]] {
+            assert_eq!(maze.generate_masked(&mask), Err(MazeError::InvalidMask));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn mask_image_round_trips() {
+        let path = std::env::temp_dir().join("krunker-maze-generator-mask-round-trip.png");
+        for seed in 0..5 {
+            let mut maze = Maze::new(9, 7).unwrap();
+            maze.generate_with_seed(seed);
+            maze.add_loops(0.2, None);
+            maze.save_mask_image(&path).unwrap();
+            let loaded = Maze::from_mask_image(&path).unwrap();
+            assert_eq!((loaded.width, loaded.height), (9, 7));
+            assert_eq!(loaded.vert_walls, maze.vert_walls, "seed {seed}");
+            assert_eq!(loaded.hor_walls, maze.hor_walls, "seed {seed}");
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn even_or_tiny_mask_images_are_rejected() {
+        let path = std::env::temp_dir().join("krunker-maze-generator-bad-mask.png");
+        for (width, height) in [(4, 5), (5, 6), (1, 1), (1, 5)] {
+            image::GrayImage::new(width, height).save(&path).unwrap();
+            assert_eq!(
+                Maze::from_mask_image(&path),
+                Err(MazeError::InvalidMaskImage { width, height })
+            );
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,237 @@
+// src/maze3d.rs
+
+#[cfg(feature = "std")]
+use crate::{MapOptions, map::textured};
+use crate::{Maze, MazeError};
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+#[cfg(feature = "std")]
+use serde_json::{Value, json};
+
+/// Steps in the staircase filling a shaft cell in `to_map_json`
+#[cfg(feature = "std")]
+const STAIR_STEPS: usize = 4;
+
+/// A stack of equally sized maze floors joined by staircases
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maze3D {
+    /// Floors from the bottom up
+    pub floors: Vec<Maze>,
+    /// `vertical_connections[f][y * width + x]` is `Some(cell)` when a
+    /// staircase leads from (x, y) on floor `f` up to `cell` on floor
+    /// `f + 1` (straight up, so `cell == (x, y)`). The top floor has no
+    /// connections.
+    pub vertical_connections: Vec<Vec<Option<(usize, usize)>>>,
+}
+
+impl Maze3D {
+    /// `floors` independently generated `width` x `height` depth-first mazes,
+    /// each joined to the next by one staircase in a random cell. The path
+    /// runs from the bottom floor's `start` to the top floor's `end`.
+    pub fn new(width: usize, height: usize, floors: usize) -> Result<Maze3D, MazeError> {
+        if floors == 0 {
+            return Err(MazeError::ZeroDimension { width, height });
+        }
+        let floors = (0..floors)
+            .map(|_| {
+                let mut maze = Maze::new(width, height)?;
+                maze.generate();
+                Ok(maze)
+            })
+            .collect::<Result<Vec<_>, MazeError>>()?;
+
+        let mut rng = StdRng::seed_from_u64(floors[0].seed);
+        let vertical_connections = (0..floors.len())
+            .map(|f| {
+                let mut shafts = vec![None; width * height];
+                if f + 1 < floors.len() {
+                    let cell = (rng.random_range(0..width), rng.random_range(0..height));
+                    shafts[cell.1 * width + cell.0] = Some(cell);
+                }
+                shafts
+            })
+            .collect();
+        Ok(Maze3D {
+            floors,
+            vertical_connections,
+        })
+    }
+
+    /// Cells reachable in one move from `(floor, x, y)`: open passages on
+    /// the same floor plus staircases up and down
+    fn neighbors(&self, (f, x, y): (usize, usize, usize)) -> Vec<(usize, usize, usize)> {
+        let maze = &self.floors[f];
+        let mut out: Vec<_> = maze
+            .open_neighbors((x, y))
+            .into_iter()
+            .map(|(nx, ny)| (f, nx, ny))
+            .collect();
+        if let Some((ux, uy)) = self.vertical_connections[f][y * maze.width + x] {
+            out.push((f + 1, ux, uy));
+        }
+        if f > 0 {
+            for (i, shaft) in self.vertical_connections[f - 1].iter().enumerate() {
+                if *shaft == Some((x, y)) {
+                    out.push((f - 1, i % maze.width, i / maze.width));
+                }
+            }
+        }
+        out
+    }
+
+    /// Shortest path as `(floor, x, y)` cells from the bottom floor's
+    /// `start` to the top floor's `end`, climbing the staircases as needed.
+    /// Fails with `MazeError::Unreachable` when there is no such path.
+    pub fn solve_3d(&self) -> Result<Vec<(usize, usize, usize)>, MazeError> {
+        let (first, last) = (&self.floors[0], &self.floors[self.floors.len() - 1]);
+        let start = (0, first.start.0, first.start.1);
+        let goal = (self.floors.len() - 1, last.end.0, last.end.1);
+        let (w, h) = (first.width, first.height);
+        let idx = |(f, x, y): (usize, usize, usize)| (f * h + y) * w + x;
+
+        let mut parent = vec![None; self.floors.len() * w * h];
+        let mut seen = vec![false; parent.len()];
+        let mut queue = VecDeque::from([start]);
+        seen[idx(start)] = true;
+        while let Some(cur) = queue.pop_front() {
+            if cur == goal {
+                let mut path = vec![cur];
+                let mut at = cur;
+                while let Some(p) = parent[idx(at)] {
+                    path.push(p);
+                    at = p;
+                }
+                path.reverse();
+                return Ok(path);
+            }
+            for n in self.neighbors(cur) {
+                if !seen[idx(n)] {
+                    seen[idx(n)] = true;
+                    parent[idx(n)] = Some(cur);
+                    queue.push_back(n);
+                }
+            }
+        }
+        Err(MazeError::Unreachable {
+            from: first.start,
+            to: last.end,
+        })
+    }
+}
+
+// Krunker map export
+#[cfg(feature = "std")]
+impl Maze3D {
+    /// One Krunker map holding every floor, `wall_height + 1` units above
+    /// the one below. Upper floor slabs are split around the stair holes,
+    /// and each shaft cell holds a staircase of `STAIR_STEPS` blocks rising
+    /// eastwards to the floor above. Only the top floor gets the ceiling
+    /// from `opts`; spawns are the bottom floor's start and the top floor's
+    /// end.
+    pub fn to_map_json(&self, opts: &MapOptions) -> Value {
+        let cs = opts.cell_size as i32;
+        let level = opts.wall_height as i32 + 1;
+        let top = self.floors.len() - 1;
+        let mut sizes: Vec<Value> = Vec::new();
+        let mut objects: Vec<Value> = Vec::new();
+        let mut spawns = Vec::new();
+
+        let mut push = |sizes: &mut Vec<Value>, size: [i32; 3], p: [i32; 3], texture: u32| {
+            let si = sizes.len() / 3;
+            sizes.extend(size.map(|v| json!(v)));
+            objects.push(textured(json!({ "p": p, "si": si }), texture));
+        };
+
+        for (f, maze) in self.floors.iter().enumerate() {
+            let base = f as i32 * level;
+            let floor_opts = MapOptions {
+                ceiling_height: if f == top { opts.ceiling_height } else { None },
+                add_ceiling: f == top && opts.add_ceiling,
+                ..opts.clone()
+            };
+            let map = maze.to_map_json(&floor_opts);
+            let xyz = map["xyz"].as_array().cloned().unwrap_or_default();
+            let floor_objects = map["objects"].as_array().cloned().unwrap_or_default();
+
+            for obj in &floor_objects {
+                let si = obj["si"].as_u64().unwrap_or(0) as usize;
+                // Upper floors replace the solid slab with one around the hole
+                if si == 0 && f > 0 {
+                    continue;
+                }
+                let size = [0, 1, 2]
+                    .map(|k| xyz.get(si * 3 + k).and_then(Value::as_i64).unwrap_or(0) as i32);
+                let p = [0, 1, 2].map(|k| obj["p"][k].as_i64().unwrap_or(0) as i32);
+                let texture = obj["ti"].as_u64().unwrap_or(0) as u32;
+                push(&mut sizes, size, [p[0], p[1] + base, p[2]], texture);
+            }
+
+            if f > 0 {
+                let (fw, fd) = (maze.width as i32 * cs, maze.height as i32 * cs);
+                let hole = self.vertical_connections[f - 1].iter().find_map(|c| *c);
+                let tiles = match hole {
+                    Some((hx, hy)) => {
+                        let (hx, hy) = (hx as i32 * cs, hy as i32 * cs);
+                        vec![
+                            (0, 0, fw, hy),
+                            (0, hy + cs, fw, fd),
+                            (0, hy, hx, hy + cs),
+                            (hx + cs, hy, fw, hy + cs),
+                        ]
+                    }
+                    None => vec![(0, 0, fw, fd)],
+                };
+                for (x0, z0, x1, z1) in tiles {
+                    if x1 > x0 && z1 > z0 {
+                        push(
+                            &mut sizes,
+                            [x1 - x0, 1, z1 - z0],
+                            [(x0 + x1) / 2, base - 1, (z0 + z1) / 2],
+                            opts.floor_texture,
+                        );
+                    }
+                }
+            }
+
+            for (i, shaft) in self.vertical_connections[f].iter().enumerate() {
+                if shaft.is_none() {
+                    continue;
+                }
+                let (x0, z0) = ((i % maze.width) as i32 * cs, (i / maze.width) as i32 * cs);
+                let steps = STAIR_STEPS as i32;
+                let depth = cs / steps;
+                for s in 0..steps {
+                    push(
+                        &mut sizes,
+                        [depth, (s + 1) * level / steps, cs],
+                        [x0 + s * depth + depth / 2, base, z0 + cs / 2],
+                        opts.floor_texture,
+                    );
+                }
+            }
+
+            let floor_spawns = map["spawns"].as_array().cloned().unwrap_or_default();
+            let mut lift = |spawn: &Value| {
+                let mut spawn = spawn.clone();
+                spawn[1] = json!(spawn[1].as_i64().unwrap_or(0) + base as i64);
+                spawns.push(spawn);
+            };
+            if f == 0
+                && let Some(s) = floor_spawns.first()
+            {
+                lift(s);
+            }
+            if f == top
+                && let Some(s) = floor_spawns.get(1)
+            {
+                lift(s);
+            }
+        }
+
+        let mut map = self.floors[0].to_map_json(opts);
+        map["xyz"] = json!(sizes);
+        map["objects"] = json!(objects);
+        map["spawns"] = json!(spawns);
+        map
+    }
+}
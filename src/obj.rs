@@ -0,0 +1,120 @@
+// src/obj.rs
+
+use crate::{MapOptions, Maze, Segment};
+
+/// Corner indices (1-based, relative to the box's first vertex) of the six
+/// faces of a box, each wound counter-clockwise seen from outside
+const BOX_FACES: [[usize; 4]; 6] = [
+    [1, 4, 3, 2], // -z
+    [5, 6, 7, 8], // +z
+    [1, 5, 8, 4], // -x
+    [2, 3, 7, 6], // +x
+    [1, 2, 6, 5], // -y
+    [4, 8, 7, 3], // +y
+];
+
+/// Accumulates named boxes as OBJ text
+struct ObjWriter {
+    out: String,
+    vertices: usize,
+}
+
+impl ObjWriter {
+    /// Axis-aligned box from `min` to `max`, as its own object
+    fn push_box(&mut self, name: &str, material: &str, min: [i32; 3], max: [i32; 3]) {
+        self.out.push_str(&format!("o {name}\nusemtl {material}\n"));
+        for &z in &[min[2], max[2]] {
+            for &(x, y) in &[
+                (min[0], min[1]),
+                (max[0], min[1]),
+                (max[0], max[1]),
+                (min[0], max[1]),
+            ] {
+                self.out.push_str(&format!("v {x} {y} {z}\n"));
+            }
+        }
+        for face in BOX_FACES {
+            let [a, b, c, d] = face.map(|i| i + self.vertices);
+            self.out.push_str(&format!("f {a} {b} {c} {d}\n"));
+        }
+        self.vertices += 8;
+    }
+}
+
+impl Maze {
+    /// The map geometry of `to_map_json` as a Wavefront OBJ document: a
+    /// floor slab, one box per wall run, door piece and waypoint pillar and
+    /// the ceiling if `opts` asks for one, in the same units and axes (y
+    /// up, z along the rows). Faces wind counter-clockwise from outside so
+    /// normals point outwards. Objects use the `floor`, `wall`, `waypoint`
+    /// and `ceiling` materials of `to_mtl_string`, referenced through
+    /// `mtllib` when given.
+    pub fn to_obj_string(&self, opts: &MapOptions, mtllib: Option<&str>) -> String {
+        let cs = opts.cell_size as i32;
+        let half_wall = opts.wall_thickness as i32 / 2;
+        let (fw, fd) = (self.width as i32 * cs, self.height as i32 * cs);
+        let height = opts.wall_height as i32;
+
+        let mut obj = ObjWriter {
+            out: String::from("# krunker-maze-generator\n"),
+            vertices: 0,
+        };
+        if let Some(lib) = mtllib {
+            obj.out.push_str(&format!("mtllib {lib}\n"));
+        }
+        obj.push_box("floor", "floor", [0, -1, 0], [fw, 0, fd]);
+        for (i, seg) in self.map_segments(opts).iter().enumerate() {
+            let (min, max) = match *seg {
+                Segment::Vertical { x, y1, y2 } => {
+                    let x = x as i32 * cs;
+                    (
+                        [x - half_wall, 0, y1 as i32 * cs],
+                        [x + half_wall, height, y2 as i32 * cs],
+                    )
+                }
+                Segment::Horizontal { y, x1, x2 } => {
+                    let z = y as i32 * cs;
+                    (
+                        [x1 as i32 * cs, 0, z - half_wall],
+                        [x2 as i32 * cs, height, z + half_wall],
+                    )
+                }
+            };
+            obj.push_box(&format!("wall_{i}"), "wall", min, max);
+        }
+        if opts.add_doors {
+            for (i, ([w, h, d], [x, y, z])) in self.map_doors(opts).into_iter().enumerate() {
+                let (min, max) = ([x - w / 2, y, z - d / 2], [x + w / 2, y + h, z + d / 2]);
+                obj.push_box(&format!("door_{i}"), "wall", min, max);
+            }
+        }
+        if opts.mark_solution_path {
+            for (i, ([w, h, d], [x, y, z])) in self.map_waypoints(opts).into_iter().enumerate() {
+                let (min, max) = (
+                    [x - w / 2, y, z - d / 2],
+                    [x + w - w / 2, y + h, z + d - d / 2],
+                );
+                obj.push_box(&format!("waypoint_{i}"), "waypoint", min, max);
+            }
+        }
+        if let Some(ceiling) = opts.ceiling() {
+            let y = ceiling as i32;
+            obj.push_box("ceiling", "ceiling", [0, y, 0], [fw, y + 1, fd]);
+        }
+        obj.out
+    }
+
+    /// Materials for `to_obj_string`: grey floor, white walls, amber
+    /// waypoints, darker grey ceiling
+    pub fn to_mtl_string() -> String {
+        [
+            ("floor", "0.55 0.55 0.55"),
+            ("wall", "0.90 0.90 0.90"),
+            ("waypoint", "0.95 0.75 0.10"),
+            ("ceiling", "0.35 0.35 0.35"),
+        ]
+        .iter()
+        .map(|(name, kd)| format!("newmtl {name}\nKa 0 0 0\nKd {kd}\nKs 0 0 0\nd 1\nillum 1\n\n"))
+        .collect()
+    }
+}
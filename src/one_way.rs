@@ -0,0 +1,72 @@
+// src/one_way.rs
+
+use crate::{Maze, MazeError};
+use alloc::vec::Vec;
+
+impl Maze {
+    /// Open the passage between neighbouring cells `from` and `to` and make
+    /// it one-way: the solvers may step from `from` to `to` but never
+    /// back. On a toroidal maze the two may be neighbours across the wrap.
+    /// Fails with `MazeError::OutOfBounds` when either cell is outside the
+    /// grid and `MazeError::NotAdjacent` when they do not touch.
+    pub fn add_one_way(
+        &mut self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Result<(), MazeError> {
+        for (x, y) in [from, to] {
+            if !self.in_bounds((x, y)) {
+                return Err(MazeError::OutOfBounds { x, y });
+            }
+        }
+        if !['R', 'L', 'D', 'U']
+            .into_iter()
+            .any(|d| self.step(from, d) == Some(to))
+        {
+            return Err(MazeError::NotAdjacent { from, to });
+        }
+        self.carve(from, to);
+        self.one_way.remove(&(to, from));
+        self.one_way.insert((from, to));
+        Ok(())
+    }
+
+    /// `open_neighbors` without the cells a one-way passage forbids
+    /// stepping to
+    pub(crate) fn moves(&self, cell: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut out = self.open_neighbors(cell);
+        if !self.one_way.is_empty() {
+            out.retain(|&n| !self.one_way.contains(&(n, cell)));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Maze;
+    use alloc::vec;
+
+    #[test]
+    fn reversed_one_way_passage_blocks_the_path() {
+        let mut maze = Maze::new(8, 6).unwrap();
+        maze.generate_with_seed(7);
+        let path = maze.solve().unwrap();
+        let dirs = maze.solution_directions();
+        let (a, b) = (path[path.len() / 2], path[path.len() / 2 + 1]);
+
+        let mut forward = maze.clone();
+        forward.add_one_way(a, b).unwrap();
+        assert_eq!(forward.solve_right_hand().unwrap().last(), Some(&maze.end));
+        assert!(forward.verify_directions(maze.start, &dirs));
+        assert_eq!(forward.count_solutions(5, usize::MAX), (1, false));
+        assert_eq!(forward.solve_all_paths(5), vec![path]);
+
+        maze.add_one_way(b, a).unwrap();
+        assert!(maze.solve().is_err());
+        assert!(maze.solve_right_hand().is_err());
+        assert!(!maze.verify_directions(maze.start, &dirs));
+        assert_eq!(maze.count_solutions(5, usize::MAX), (0, false));
+        assert!(maze.solve_all_paths(5).is_empty());
+    }
+}
@@ -0,0 +1,48 @@
+// src/parallel.rs
+
+use crate::Maze;
+use rand::Rng;
+use rayon::prelude::*;
+
+impl Maze {
+    /// Depth-first generation split across `threads` worker threads: the
+    /// grid is cut into up to `threads` horizontal stripes, each carved as
+    /// its own spanning tree in parallel, then every pair of neighbouring
+    /// stripes is joined through exactly one random wall. The result is
+    /// still a perfect maze. Expects a maze with every wall standing, like
+    /// `generate`.
+    pub fn generate_parallel(&mut self, threads: usize) {
+        let stripes = threads.clamp(1, self.height);
+        // Row where each stripe starts, plus the end of the grid
+        let bounds: Vec<usize> = (0..=stripes).map(|i| i * self.height / stripes).collect();
+        let (width, seed) = (self.width, self.seed);
+
+        let carve = |i: usize| {
+            let mut stripe =
+                Maze::new(width, bounds[i + 1] - bounds[i]).expect("stripes have at least one row");
+            stripe.generate_with_seed(seed.wrapping_add(i as u64 + 1));
+            stripe
+        };
+        let carved: Vec<Maze> = match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+            Ok(pool) => pool.install(|| (0..stripes).into_par_iter().map(carve).collect()),
+            Err(_) => (0..stripes).map(carve).collect(),
+        };
+
+        for (stripe, &y0) in carved.into_iter().zip(&bounds) {
+            let rows = stripe.height;
+            for (y, row) in stripe.vert_walls.into_iter().enumerate() {
+                self.vert_walls[y0 + y] = row;
+            }
+            // The stripe's top and bottom rows stay solid until joined below
+            for (y, row) in stripe.hor_walls.into_iter().enumerate().take(rows).skip(1) {
+                self.hor_walls[y0 + y] = row;
+            }
+        }
+
+        let mut rng = self.seeded_rng();
+        for &y in &bounds[1..stripes] {
+            let x = rng.random_range(0..self.width);
+            self.carve((x, y - 1), (x, y));
+        }
+    }
+}
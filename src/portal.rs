@@ -0,0 +1,101 @@
+// src/portal.rs
+
+use crate::{Maze, MazeError};
+use alloc::vec::Vec;
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+
+/// A two-way teleporter joining two cells: stepping onto either end moves
+/// a player to the other at no cost
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Portal {
+    pub cell_a: (usize, usize),
+    pub cell_b: (usize, usize),
+}
+
+impl Portal {
+    /// The far end of the portal when `cell` is one of its ends
+    pub(crate) fn partner(&self, cell: (usize, usize)) -> Option<(usize, usize)> {
+        if cell == self.cell_a {
+            Some(self.cell_b)
+        } else if cell == self.cell_b {
+            Some(self.cell_a)
+        } else {
+            None
+        }
+    }
+}
+
+/// A cell of a path returned by `Maze::solve_with_portals`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PathStep {
+    pub cell: (usize, usize),
+    /// The path teleports from or onto this cell
+    pub portal: bool,
+}
+
+impl Maze {
+    /// Join cells `a` and `b` with a portal, which `solve` and
+    /// `solve_between` then treat as a free move between them; walls are
+    /// left alone. Fails with `MazeError::OutOfBounds` when either cell is
+    /// outside the grid.
+    pub fn add_portal(&mut self, a: (usize, usize), b: (usize, usize)) -> Result<(), MazeError> {
+        for (x, y) in [a, b] {
+            if !self.in_bounds((x, y)) {
+                return Err(MazeError::OutOfBounds { x, y });
+            }
+        }
+        self.portals.push(Portal {
+            cell_a: a,
+            cell_b: b,
+        });
+        Ok(())
+    }
+
+    /// Add `count` portals between random cells. No cell gets more than one
+    /// portal end and `start` and `end` get none, so fewer pairs are placed
+    /// when the grid runs out of cells. Without an explicit `rng_seed` the
+    /// maze's own seed is used, keeping seeded runs reproducible.
+    pub fn add_portal_pairs(&mut self, count: usize, rng_seed: Option<u64>) {
+        let mut rng = StdRng::seed_from_u64(rng_seed.unwrap_or(self.seed));
+        let mut cells: Vec<_> = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&c| {
+                c != self.start && c != self.end && self.portal_partners(c).next().is_none()
+            })
+            .collect();
+        let pairs = count.min(cells.len() / 2);
+        let (chosen, _) = cells.partial_shuffle(&mut rng, pairs * 2);
+        for pair in chosen.chunks_exact(2) {
+            self.portals.push(Portal {
+                cell_a: pair[0],
+                cell_b: pair[1],
+            });
+        }
+    }
+
+    /// Cells a portal at `cell` leads to
+    pub(crate) fn portal_partners(
+        &self,
+        cell: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.portals.iter().filter_map(move |p| p.partner(cell))
+    }
+
+    /// `solve`, with every cell the path teleports from or onto marked.
+    /// Consecutive steps joined by a portal are usually not adjacent in
+    /// the grid, which is why `draw_path` leaves a gap there.
+    pub fn solve_with_portals(&self) -> Result<Vec<PathStep>, MazeError> {
+        let path = self.solve()?;
+        let jump = |a: (usize, usize), b| self.portal_partners(a).any(|n| n == b);
+        Ok(path
+            .iter()
+            .enumerate()
+            .map(|(i, &cell)| PathStep {
+                cell,
+                portal: (i > 0 && jump(path[i - 1], cell))
+                    || path.get(i + 1).is_some_and(|&next| jump(cell, next)),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,129 @@
+// src/rooms.rs
+
+use crate::{Maze, generate::DisjointSet};
+use alloc::{vec, vec::Vec};
+use rand::{Rng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+
+/// Placement attempts per requested room before giving up on it
+const ROOM_ATTEMPTS: usize = 50;
+
+/// An open rectangular area with no interior walls, in cell units
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Room {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Room {
+    fn overlaps(&self, other: &Room) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+
+    /// Whether the cell lies inside the room
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+impl Maze {
+    /// Generate a maze with open rooms: place up to `count` non-overlapping
+    /// rooms sized between `min_size` and `max_size` (width, height), clear
+    /// their interiors, fill the remaining cells with backtracking corridors,
+    /// then open random walls until rooms and corridors form one connected
+    /// maze. Every room ends up with at least one doorway.
+    ///
+    /// Call on a fresh, fully walled maze. Rooms that cannot be placed after
+    /// repeated attempts are skipped; the placed ones are recorded in
+    /// `self.rooms`.
+    pub fn carve_rooms(
+        &mut self,
+        count: usize,
+        min_size: (usize, usize),
+        max_size: (usize, usize),
+    ) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let mut rng = self.seeded_rng();
+        let max_w = max_size.0.clamp(1, self.width);
+        let max_h = max_size.1.clamp(1, self.height);
+        let min_w = min_size.0.clamp(1, max_w);
+        let min_h = min_size.1.clamp(1, max_h);
+
+        let mut placed: Vec<Room> = Vec::new();
+        for _ in 0..count * ROOM_ATTEMPTS {
+            if placed.len() == count {
+                break;
+            }
+            let width = rng.random_range(min_w..=max_w);
+            let height = rng.random_range(min_h..=max_h);
+            let room = Room {
+                x: rng.random_range(0..=self.width - width),
+                y: rng.random_range(0..=self.height - height),
+                width,
+                height,
+            };
+            if placed.iter().all(|r| !r.overlaps(&room)) {
+                placed.push(room);
+            }
+        }
+
+        let mut visited = vec![vec![false; self.width]; self.height];
+        for room in &placed {
+            let (xs, ys) = (room.x..room.x + room.width, room.y..room.y + room.height);
+            for row in &mut visited[ys.clone()] {
+                row[xs.clone()].fill(true);
+            }
+            for row in &mut self.vert_walls[ys.clone()] {
+                row[xs.start + 1..xs.end].fill(false);
+            }
+            for row in &mut self.hor_walls[ys.start + 1..ys.end] {
+                row[xs.clone()].fill(false);
+            }
+        }
+
+        // Corridors; rooms can split the free cells into several regions
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !visited[y][x] {
+                    visited[y][x] = true;
+                    self.backtrack_from((x, y), &mut visited, &mut rng, &mut || {});
+                }
+            }
+        }
+
+        // Join rooms and corridor regions with one random wall per merge
+        let width = self.width;
+        let idx = move |(x, y): (usize, usize)| y * width + x;
+        let mut sets = DisjointSet::new(self.width * self.height);
+        let mut walls = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                for n in [(x + 1, y), (x, y + 1)] {
+                    if !self.in_bounds(n) {
+                        continue;
+                    }
+                    if self.open_neighbors((x, y)).contains(&n) {
+                        sets.union(idx((x, y)), idx(n));
+                    } else {
+                        walls.push(((x, y), n));
+                    }
+                }
+            }
+        }
+        walls.shuffle(&mut rng);
+        for (a, b) in walls {
+            if sets.union(idx(a), idx(b)) {
+                self.carve(a, b);
+            }
+        }
+
+        self.rooms.extend(placed);
+    }
+}
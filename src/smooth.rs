@@ -0,0 +1,52 @@
+// src/smooth.rs
+
+use crate::Maze;
+use alloc::vec::Vec;
+
+/// Points sampled along each rounded corner of `Maze::smooth_path`
+const CORNER_STEPS: usize = 8;
+
+impl Maze {
+    /// Round the right-angle turns of a path of adjacent cells, such as
+    /// `solve` returns, for smoother drawings and bot navigation. Points
+    /// are in cell units with cell (x, y) spanning x..x + 1 and y..y + 1,
+    /// so multiply by the cell size for pixels or map units. Straight runs
+    /// pass through the cell centres; each turn becomes a quadratic Bézier
+    /// curve from the middle of the edge it enters by to the middle of the
+    /// edge it leaves by, with the cell centre as its control point,
+    /// sampled at a few points. The curve stays inside the turning cell,
+    /// so it never crosses a wall the path did not. Split the path at
+    /// toroidal wrap steps first: the two sides of a wrap are not joined.
+    pub fn smooth_path(path: &[(usize, usize)]) -> Vec<(f64, f64)> {
+        let centre = |(x, y): (usize, usize)| (x as f64 + 0.5, y as f64 + 0.5);
+        let mid = |a: (f64, f64), b: (f64, f64)| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+        let mut points = Vec::with_capacity(path.len() + CORNER_STEPS);
+        let Some(&first) = path.first() else {
+            return points;
+        };
+        points.push(centre(first));
+        for turn in path.windows(3) {
+            let [prev, cell, next] = [turn[0], turn[1], turn[2]].map(centre);
+            let straight = (cell.0 - prev.0, cell.1 - prev.1) == (next.0 - cell.0, next.1 - cell.1);
+            if straight {
+                points.push(cell);
+                continue;
+            }
+            let (from, to) = (mid(prev, cell), mid(cell, next));
+            // Back-to-back turns share the edge between them
+            let first = usize::from(points.last() == Some(&from));
+            for k in first..=CORNER_STEPS {
+                let t = k as f64 / CORNER_STEPS as f64;
+                let (a, b, c) = ((1.0 - t) * (1.0 - t), 2.0 * (1.0 - t) * t, t * t);
+                points.push((
+                    a * from.0 + b * cell.0 + c * to.0,
+                    a * from.1 + b * cell.1 + c * to.1,
+                ));
+            }
+        }
+        if path.len() > 1 {
+            points.push(centre(path[path.len() - 1]));
+        }
+        points
+    }
+}
@@ -0,0 +1,483 @@
+// src/sparse.rs
+
+use crate::{Maze, MazeError};
+use alloc::{collections::VecDeque, string::String, vec, vec::Vec};
+use rand::{SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+
+/// Mazes with more cells than this are stored as a `SparseMaze` by
+/// `AnyMaze::new`
+#[cfg(feature = "std")]
+pub const SPARSE_THRESHOLD: usize = 250_000;
+
+/// Which wall of cell `(x, y)` is meant. Storage only uses `North` and
+/// `West`: the east and south walls are the west and north walls of the
+/// next cell, so the grid edges use `x == width` and `y == height`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WallSide {
+    /// `hor_walls[y][x]` in a `Maze`
+    North,
+    /// `hor_walls[y + 1][x]` in a `Maze`
+    South,
+    /// `vert_walls[y][x + 1]` in a `Maze`
+    East,
+    /// `vert_walls[y][x]` in a `Maze`
+    West,
+}
+
+impl WallSide {
+    /// The same wall named from the cell it is the `North` or `West` side of
+    pub(crate) fn canonical(self, x: usize, y: usize) -> (usize, usize, WallSide) {
+        match self {
+            WallSide::South => (x, y + 1, WallSide::North),
+            WallSide::East => (x + 1, y, WallSide::West),
+            side => (x, y, side),
+        }
+    }
+}
+
+/// Operations shared by the dense `Maze` and the `SparseMaze`. Implementors
+/// provide the dimensions, endpoints and wall storage; generation, solving
+/// and text export come for free.
+pub trait MazeLike {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn start(&self) -> (usize, usize);
+    fn end(&self) -> (usize, usize);
+    /// Whether the wall is standing
+    fn has_wall(&self, x: usize, y: usize, side: WallSide) -> bool;
+    /// Raise (`true`) or knock down (`false`) a wall
+    fn set_wall(&mut self, x: usize, y: usize, side: WallSide, standing: bool);
+
+    /// Orthogonal neighbours reachable without crossing a wall
+    fn open_neighbors(&self, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+        let mut out = Vec::with_capacity(4);
+        if x > 0 && !self.has_wall(x, y, WallSide::West) {
+            out.push((x - 1, y));
+        }
+        if x + 1 < self.width() && !self.has_wall(x + 1, y, WallSide::West) {
+            out.push((x + 1, y));
+        }
+        if y > 0 && !self.has_wall(x, y, WallSide::North) {
+            out.push((x, y - 1));
+        }
+        if y + 1 < self.height() && !self.has_wall(x, y + 1, WallSide::North) {
+            out.push((x, y + 1));
+        }
+        out
+    }
+
+    /// Depth-first backtracking from (0, 0) over a grid with every wall
+    /// standing, seeded by `seed`. Neighbours are tried in the order of
+    /// `Maze::generate`, so a seed gives the same layout in every
+    /// representation.
+    fn generate_with_seed(&mut self, seed: u64) {
+        let (w, h) = (self.width(), self.height());
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut visited = vec![false; w * h];
+        visited[0] = true;
+        let mut stack = vec![(0, 0)];
+        while let Some(&(x, y)) = stack.last() {
+            let mut unvisited = Vec::with_capacity(4);
+            if x + 1 < w {
+                unvisited.push((x + 1, y));
+            }
+            if x > 0 {
+                unvisited.push((x - 1, y));
+            }
+            if y + 1 < h {
+                unvisited.push((x, y + 1));
+            }
+            if y > 0 {
+                unvisited.push((x, y - 1));
+            }
+            unvisited.retain(|&(nx, ny)| !visited[ny * w + nx]);
+            match unvisited.choose(&mut rng) {
+                Some(&(nx, ny)) => {
+                    if ny == y {
+                        self.set_wall(x.max(nx), y, WallSide::West, false);
+                    } else {
+                        self.set_wall(x, y.max(ny), WallSide::North, false);
+                    }
+                    visited[ny * w + nx] = true;
+                    stack.push((nx, ny));
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    /// Shortest path from `start` to `end`, failing like `Maze::solve`
+    fn solve(&self) -> Result<Vec<(usize, usize)>, MazeError> {
+        let (w, h) = (self.width(), self.height());
+        let (start, end) = (self.start(), self.end());
+        for (x, y) in [start, end] {
+            if x >= w || y >= h {
+                return Err(MazeError::OutOfBounds { x, y });
+            }
+        }
+        let mut parent = vec![None; w * h];
+        let mut seen = vec![false; w * h];
+        seen[start.1 * w + start.0] = true;
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            if cell == end {
+                let mut path = vec![cell];
+                let mut at = cell;
+                while let Some(p) = parent[at.1 * w + at.0] {
+                    path.push(p);
+                    at = p;
+                }
+                path.reverse();
+                return Ok(path);
+            }
+            for (nx, ny) in self.open_neighbors(cell) {
+                if !seen[ny * w + nx] {
+                    seen[ny * w + nx] = true;
+                    parent[ny * w + nx] = Some(cell);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        Err(MazeError::Unreachable {
+            from: start,
+            to: end,
+        })
+    }
+
+    /// Text rendering in the format of `Maze::to_ascii`
+    fn to_ascii(&self) -> String {
+        let wall = |closed: bool| if closed { '#' } else { ' ' };
+        let mut out = String::with_capacity((self.width() * 3 + 1) * self.height() * 3);
+        for y in 0..self.height() {
+            let mut rows = [String::new(), String::new(), String::new()];
+            for x in 0..self.width() {
+                let centre = if (x, y) == self.start() && (x, y) == self.end() {
+                    'X'
+                } else if (x, y) == self.start() {
+                    'S'
+                } else if (x, y) == self.end() {
+                    'E'
+                } else {
+                    ' '
+                };
+                rows[0].extend(['#', wall(self.has_wall(x, y, WallSide::North)), '#']);
+                rows[1].extend([
+                    wall(self.has_wall(x, y, WallSide::West)),
+                    centre,
+                    wall(self.has_wall(x + 1, y, WallSide::West)),
+                ]);
+                rows[2].extend(['#', wall(self.has_wall(x, y + 1, WallSide::North)), '#']);
+            }
+            for row in rows {
+                out.push_str(&row);
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+impl MazeLike for Maze {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    fn end(&self) -> (usize, usize) {
+        self.end
+    }
+
+    fn has_wall(&self, x: usize, y: usize, side: WallSide) -> bool {
+        match side.canonical(x, y) {
+            (x, y, WallSide::North) => self.hor_walls[y][x],
+            (x, y, _) => self.vert_walls[y][x],
+        }
+    }
+
+    fn set_wall(&mut self, x: usize, y: usize, side: WallSide, standing: bool) {
+        match side.canonical(x, y) {
+            (x, y, WallSide::North) => self.hor_walls[y][x] = standing,
+            (x, y, _) => self.vert_walls[y][x] = standing,
+        }
+    }
+
+    fn open_neighbors(&self, cell: (usize, usize)) -> Vec<(usize, usize)> {
+        Maze::open_neighbors(self, cell)
+    }
+
+    fn generate_with_seed(&mut self, seed: u64) {
+        Maze::generate_with_seed(self, seed);
+    }
+
+    fn solve(&self) -> Result<Vec<(usize, usize)>, MazeError> {
+        Maze::solve(self)
+    }
+
+    fn to_ascii(&self) -> String {
+        Maze::to_ascii(self)
+    }
+}
+
+/// A maze that packs its walls into a bitset: two bits, `North` and
+/// `West` (see `WallSide`), per corner of the grid, set while the wall
+/// stands. That is about an eighth of the memory of `Maze`, which spends a
+/// `bool` on each wall plus a `Vec` per row. Starts fully walled, like
+/// `Maze::new`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseMaze {
+    pub width: usize,
+    pub height: usize,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+    walls: Vec<u64>,
+}
+
+#[cfg(feature = "std")]
+impl SparseMaze {
+    /// A `width` x `height` grid with every wall standing, from the top-left
+    /// to the bottom-right corner
+    pub fn new(width: usize, height: usize) -> Result<SparseMaze, MazeError> {
+        if width == 0 || height == 0 {
+            return Err(MazeError::ZeroDimension { width, height });
+        }
+        let mut maze = SparseMaze::empty(width, height);
+        for y in 0..=height {
+            for x in 0..=width {
+                if x < width {
+                    maze.set_wall(x, y, WallSide::North, true);
+                }
+                if y < height {
+                    maze.set_wall(x, y, WallSide::West, true);
+                }
+            }
+        }
+        Ok(maze)
+    }
+
+    /// A grid without a single wall
+    fn empty(width: usize, height: usize) -> SparseMaze {
+        let bits = 2 * (width + 1) * (height + 1);
+        SparseMaze {
+            width,
+            height,
+            start: (0, 0),
+            end: (width - 1, height - 1),
+            walls: vec![0; bits.div_ceil(64)],
+        }
+    }
+
+    /// Index of a wall's bit in `walls`
+    fn bit(&self, x: usize, y: usize, side: WallSide) -> usize {
+        let (x, y, side) = side.canonical(x, y);
+        2 * (y * (self.width + 1) + x) + usize::from(side == WallSide::West)
+    }
+
+    /// Number of walls still standing
+    pub fn wall_count(&self) -> usize {
+        self.walls.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// The same maze in the dense representation used by drawing and export
+    pub fn to_maze(&self) -> Maze {
+        let mut maze = Maze::new(self.width, self.height).expect("non-zero dimensions");
+        maze.start = self.start;
+        maze.end = self.end;
+        for y in 0..=self.height {
+            for x in 0..=self.width {
+                if x < self.width {
+                    maze.hor_walls[y][x] = self.has_wall(x, y, WallSide::North);
+                }
+                if y < self.height {
+                    maze.vert_walls[y][x] = self.has_wall(x, y, WallSide::West);
+                }
+            }
+        }
+        maze
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<&Maze> for SparseMaze {
+    fn from(maze: &Maze) -> Self {
+        let mut sparse = SparseMaze::empty(maze.width, maze.height);
+        sparse.start = maze.start;
+        sparse.end = maze.end;
+        for y in 0..=maze.height {
+            for x in 0..=maze.width {
+                if x < maze.width && maze.hor_walls[y][x] {
+                    sparse.set_wall(x, y, WallSide::North, true);
+                }
+                if y < maze.height && maze.vert_walls[y][x] {
+                    sparse.set_wall(x, y, WallSide::West, true);
+                }
+            }
+        }
+        sparse
+    }
+}
+
+#[cfg(feature = "std")]
+impl MazeLike for SparseMaze {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    fn end(&self) -> (usize, usize) {
+        self.end
+    }
+
+    fn has_wall(&self, x: usize, y: usize, side: WallSide) -> bool {
+        let bit = self.bit(x, y, side);
+        self.walls[bit / 64] & (1 << (bit % 64)) != 0
+    }
+
+    fn set_wall(&mut self, x: usize, y: usize, side: WallSide, standing: bool) {
+        let bit = self.bit(x, y, side);
+        if standing {
+            self.walls[bit / 64] |= 1 << (bit % 64);
+        } else {
+            self.walls[bit / 64] &= !(1 << (bit % 64));
+        }
+    }
+}
+
+/// A `Maze` or, above `SPARSE_THRESHOLD` cells, a `SparseMaze`
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnyMaze {
+    Dense(Maze),
+    Sparse(SparseMaze),
+}
+
+#[cfg(feature = "std")]
+impl AnyMaze {
+    /// A fully walled grid in whichever representation suits its size
+    pub fn new(width: usize, height: usize) -> Result<AnyMaze, MazeError> {
+        if width * height > SPARSE_THRESHOLD {
+            SparseMaze::new(width, height).map(AnyMaze::Sparse)
+        } else {
+            Maze::new(width, height).map(AnyMaze::Dense)
+        }
+    }
+
+    /// The maze in the dense representation
+    pub fn into_maze(self) -> Maze {
+        match self {
+            AnyMaze::Dense(maze) => maze,
+            AnyMaze::Sparse(maze) => maze.to_maze(),
+        }
+    }
+
+    fn inner(&self) -> &dyn MazeLike {
+        match self {
+            AnyMaze::Dense(maze) => maze,
+            AnyMaze::Sparse(maze) => maze,
+        }
+    }
+
+    fn inner_mut(&mut self) -> &mut dyn MazeLike {
+        match self {
+            AnyMaze::Dense(maze) => maze,
+            AnyMaze::Sparse(maze) => maze,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl MazeLike for AnyMaze {
+    fn width(&self) -> usize {
+        self.inner().width()
+    }
+
+    fn height(&self) -> usize {
+        self.inner().height()
+    }
+
+    fn start(&self) -> (usize, usize) {
+        self.inner().start()
+    }
+
+    fn end(&self) -> (usize, usize) {
+        self.inner().end()
+    }
+
+    fn has_wall(&self, x: usize, y: usize, side: WallSide) -> bool {
+        self.inner().has_wall(x, y, side)
+    }
+
+    fn set_wall(&mut self, x: usize, y: usize, side: WallSide, standing: bool) {
+        self.inner_mut().set_wall(x, y, side, standing);
+    }
+
+    fn open_neighbors(&self, cell: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner().open_neighbors(cell)
+    }
+
+    fn generate_with_seed(&mut self, seed: u64) {
+        self.inner_mut().generate_with_seed(seed);
+    }
+
+    fn solve(&self) -> Result<Vec<(usize, usize)>, MazeError> {
+        self.inner().solve()
+    }
+
+    fn to_ascii(&self) -> String {
+        self.inner().to_ascii()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::{MazeLike, SparseMaze, WallSide};
+    use crate::Maze;
+
+    #[test]
+    fn dense_and_sparse_generate_the_same_maze() {
+        for seed in 0..10 {
+            let mut dense = Maze::new(13, 8).unwrap();
+            MazeLike::generate_with_seed(&mut dense, seed);
+            let mut sparse = SparseMaze::new(13, 8).unwrap();
+            sparse.generate_with_seed(seed);
+
+            let back = sparse.to_maze();
+            assert_eq!(back.vert_walls, dense.vert_walls, "seed {seed}");
+            assert_eq!(back.hor_walls, dense.hor_walls, "seed {seed}");
+            assert_eq!(SparseMaze::from(&dense), sparse, "seed {seed}");
+            assert_eq!(sparse.to_ascii(), MazeLike::to_ascii(&dense));
+            assert_eq!(MazeLike::solve(&sparse), dense.solve());
+        }
+    }
+
+    #[test]
+    fn sparse_walls_follow_set_wall() {
+        let mut maze = SparseMaze::new(4, 3).unwrap();
+        // Every wall slot: 4 * 4 north and 5 * 3 west
+        assert_eq!(maze.wall_count(), 4 * 4 + 5 * 3);
+        maze.set_wall(1, 1, WallSide::East, false);
+        assert!(!maze.has_wall(2, 1, WallSide::West));
+        assert!(maze.has_wall(1, 1, WallSide::South));
+        assert_eq!(maze.open_neighbors((1, 1)), vec![(2, 1)]);
+        maze.set_wall(2, 1, WallSide::West, true);
+        assert_eq!(maze.wall_count(), 4 * 4 + 5 * 3);
+    }
+}
@@ -0,0 +1,207 @@
+// src/stl.rs
+
+use crate::{Maze, Segment};
+use alloc::{format, string::String, vec::Vec};
+
+/// Sizes, in millimetres, of the model `to_stl_string` and `to_stl_bytes`
+/// build for 3D printing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StlOptions {
+    /// Width of a cell; shrunk when needed so the model fits `bed_size`
+    pub cell_size: f64,
+    pub wall_thickness: f64,
+    /// Height of the walls above the floor
+    pub wall_height: f64,
+    pub floor_thickness: f64,
+    /// Side of the square print bed the model must fit on
+    pub bed_size: f64,
+}
+
+impl Default for StlOptions {
+    fn default() -> Self {
+        StlOptions {
+            cell_size: 5.0,
+            wall_thickness: 1.2,
+            wall_height: 5.0,
+            floor_thickness: 1.0,
+            bed_size: 200.0,
+        }
+    }
+}
+
+impl StlOptions {
+    /// `cell_size` for a `width` x `height` maze, reduced so the floor with
+    /// its outer walls is at most `bed_size` on each side
+    pub fn fitted_cell_size(&self, width: usize, height: usize) -> f64 {
+        let fit = (self.bed_size - self.wall_thickness) / width.max(height) as f64;
+        self.cell_size.min(fit)
+    }
+}
+
+/// A triangle: facet normal, then its corners counter-clockwise seen from
+/// outside
+type Triangle = ([f32; 3], [[f32; 3]; 3]);
+
+/// The 12 triangles of the axis-aligned box from `min` to `max`
+fn box_triangles(min: [f32; 3], max: [f32; 3]) -> [Triangle; 12] {
+    let corner = |i: usize| {
+        [
+            if i & 1 == 0 { min[0] } else { max[0] },
+            if i & 2 == 0 { min[1] } else { max[1] },
+            if i & 4 == 0 { min[2] } else { max[2] },
+        ]
+    };
+    // Each face as its normal and corner indices (bit 0 = x, 1 = y, 2 = z)
+    // counter-clockwise from outside
+    let faces: [([f32; 3], [usize; 4]); 6] = [
+        ([-1.0, 0.0, 0.0], [0, 4, 6, 2]),
+        ([1.0, 0.0, 0.0], [1, 3, 7, 5]),
+        ([0.0, -1.0, 0.0], [0, 1, 5, 4]),
+        ([0.0, 1.0, 0.0], [2, 6, 7, 3]),
+        ([0.0, 0.0, -1.0], [0, 2, 3, 1]),
+        ([0.0, 0.0, 1.0], [4, 5, 7, 6]),
+    ];
+    let mut out = [([0.0; 3], [[0.0; 3]; 3]); 12];
+    for (i, (normal, [a, b, c, d])) in faces.into_iter().enumerate() {
+        out[2 * i] = (normal, [corner(a), corner(b), corner(c)]);
+        out[2 * i + 1] = (normal, [corner(a), corner(c), corner(d)]);
+    }
+    out
+}
+
+impl Maze {
+    /// The model as triangles, in millimetres with z up: a floor slab
+    /// covering the grid and, standing on it, one box per wall run
+    fn stl_triangles(&self, opts: &StlOptions) -> Vec<Triangle> {
+        let cell = opts.fitted_cell_size(self.width, self.height);
+        let (thick, floor) = (opts.wall_thickness, opts.floor_thickness);
+        let top = floor + opts.wall_height;
+        let w = self.width as f64 * cell + thick;
+        let d = self.height as f64 * cell + thick;
+        let mut boxes = Vec::from([([0.0, 0.0, 0.0], [w, d, floor])]);
+        for seg in self.wall_segments() {
+            boxes.push(match seg {
+                Segment::Vertical { x, y1, y2 } => {
+                    let x = x as f64 * cell;
+                    (
+                        [x, y1 as f64 * cell, floor],
+                        [x + thick, y2 as f64 * cell + thick, top],
+                    )
+                }
+                Segment::Horizontal { y, x1, x2 } => {
+                    let y = y as f64 * cell;
+                    (
+                        [x1 as f64 * cell, y, floor],
+                        [x2 as f64 * cell + thick, y + thick, top],
+                    )
+                }
+            });
+        }
+        // Rows run along +y in the drawings but the model is seen from
+        // above, so flip y to keep the layout the right way round
+        boxes
+            .into_iter()
+            .flat_map(|(min, max)| {
+                box_triangles(
+                    [min[0] as f32, (d - max[1]) as f32, min[2] as f32],
+                    [max[0] as f32, (d - min[1]) as f32, max[2] as f32],
+                )
+            })
+            .collect()
+    }
+
+    /// The walls and floor as an ASCII STL document for 3D printing, sized
+    /// by `opts` (see `StlOptions`). Every wall run and the floor is a
+    /// closed box of 12 triangles; overlapping boxes at the corners are
+    /// merged by the slicer.
+    pub fn to_stl_string(&self, opts: &StlOptions) -> String {
+        let mut out = String::from("solid maze\n");
+        for (n, corners) in self.stl_triangles(opts) {
+            out.push_str(&format!("  facet normal {} {} {}\n", n[0], n[1], n[2]));
+            out.push_str("    outer loop\n");
+            for [x, y, z] in corners {
+                out.push_str(&format!("      vertex {x} {y} {z}\n"));
+            }
+            out.push_str("    endloop\n  endfacet\n");
+        }
+        out.push_str("endsolid maze\n");
+        out
+    }
+
+    /// `to_stl_string` as binary STL: an 80-byte header, the triangle
+    /// count and 50 bytes per triangle, all little-endian
+    pub fn to_stl_bytes(&self, opts: &StlOptions) -> Vec<u8> {
+        let triangles = self.stl_triangles(opts);
+        let mut out = Vec::with_capacity(84 + 50 * triangles.len());
+        let mut header = [0u8; 80];
+        let title = b"krunker-maze-generator";
+        header[..title.len()].copy_from_slice(title);
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+        for (normal, corners) in triangles {
+            for v in core::iter::once(normal).chain(corners) {
+                for c in v {
+                    out.extend_from_slice(&c.to_le_bytes());
+                }
+            }
+            out.extend_from_slice(&0u16.to_le_bytes());
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Maze, StlOptions};
+    use alloc::vec::Vec;
+
+    fn generated(width: usize, height: usize) -> Maze {
+        let mut maze = Maze::new(width, height).unwrap();
+        maze.generate_with_seed(8);
+        maze
+    }
+
+    /// Every vertex of a binary STL document
+    fn vertices(bytes: &[u8]) -> Vec<[f32; 3]> {
+        let float = |at: usize| f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        // Each 50-byte triangle starts with its normal, then the corners
+        (84..bytes.len())
+            .step_by(50)
+            .flat_map(|triangle| (1..4).map(move |corner| triangle + 12 * corner))
+            .map(|at| [float(at), float(at + 4), float(at + 8)])
+            .collect()
+    }
+
+    #[test]
+    fn one_box_for_the_floor_and_each_wall_run() {
+        let maze = generated(7, 5);
+        let opts = StlOptions::default();
+        let triangles = 12 * (1 + maze.wall_segments().len());
+        let bytes = maze.to_stl_bytes(&opts);
+        assert!(bytes.starts_with(b"krunker-maze-generator"));
+        assert_eq!(bytes.len(), 84 + 50 * triangles);
+        assert_eq!(
+            u32::from_le_bytes(bytes[80..84].try_into().unwrap()) as usize,
+            triangles
+        );
+        let text = maze.to_stl_string(&opts);
+        assert_eq!(text.matches("facet normal").count(), triangles);
+        assert_eq!(text.matches("vertex").count(), 3 * triangles);
+    }
+
+    #[test]
+    fn default_scale_fits_the_bed() {
+        let opts = StlOptions::default();
+        let top = (opts.floor_thickness + opts.wall_height) as f32;
+        for (width, height, side) in [(10, 6, 51.2), (60, 45, 200.0)] {
+            let maze = generated(width, height);
+            let corners = vertices(&maze.to_stl_bytes(&opts));
+            let max = |axis: usize| corners.iter().map(|v| v[axis]).fold(f32::MIN, f32::max);
+            let min = |axis: usize| corners.iter().map(|v| v[axis]).fold(f32::MAX, f32::min);
+            assert_eq!([min(0), min(1), min(2)], [0.0; 3]);
+            assert!((max(0) - side).abs() < 1e-3, "{width}x{height}: {}", max(0));
+            assert!(max(1) <= max(0));
+            assert!((max(2) - top).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+// src/storage.rs
+
+#[cfg(feature = "ron")]
+use crate::MazeError;
+use crate::{Algorithm, Maze, MazeBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// A maze in the generation cache with what produced it. The crate version
+/// is kept because a generator change can turn the same seed into a
+/// different layout.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    version: String,
+    width: usize,
+    height: usize,
+    seed: u64,
+    algorithm: Algorithm,
+    fingerprint: u64,
+    maze: Maze,
+}
+
+/// Cache file for one set of generation parameters, e.g.
+/// `40x30-aldous-broder-7.json`
+fn cache_path(dir: &Path, width: usize, height: usize, seed: u64, algo: Algorithm) -> PathBuf {
+    let algo = serde_json::to_value(algo)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_owned))
+        .unwrap_or_default();
+    dir.join(format!("{width}x{height}-{algo}-{seed}.json"))
+}
+
+impl Maze {
+    /// Write the maze (dimensions, walls and seed) to `path` as JSON
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut w, self)?;
+        w.flush()
+    }
+
+    /// Read a maze previously written by `save`
+    pub fn load(path: &Path) -> io::Result<Maze> {
+        let maze: Maze = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        if !maze.has_valid_shape() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "wall arrays or start/end cells do not match the maze dimensions",
+            ));
+        }
+        Ok(maze)
+    }
+
+    /// Store the maze in the generation cache under `dir` (created if
+    /// needed), keyed by its size, `seed` and `algo`, together with its
+    /// `fingerprint`. `algo` must be the algorithm that carved it, and the
+    /// maze should be exactly what `MazeBuilder` built from those
+    /// parameters, or later lookups return the wrong layout.
+    pub fn save_cached(&self, dir: &Path, algo: Algorithm) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let entry = CacheEntry {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            width: self.width,
+            height: self.height,
+            seed: self.seed,
+            algorithm: algo,
+            fingerprint: self.fingerprint(),
+            maze: self.clone(),
+        };
+        let path = cache_path(dir, self.width, self.height, self.seed, algo);
+        let mut w = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut w, &entry)?;
+        w.flush()
+    }
+
+    /// The cached maze for these parameters, if `save_cached` stored one
+    /// with this crate version. Unreadable entries, and entries whose walls
+    /// no longer match their fingerprint, count as misses.
+    pub fn load_cached(
+        dir: &Path,
+        width: usize,
+        height: usize,
+        seed: u64,
+        algo: Algorithm,
+    ) -> Option<Maze> {
+        let file = File::open(cache_path(dir, width, height, seed, algo)).ok()?;
+        let entry: CacheEntry = serde_json::from_reader(BufReader::new(file)).ok()?;
+        let maze = entry.maze;
+        (entry.version == env!("CARGO_PKG_VERSION")
+            && (entry.width, entry.height, entry.seed) == (width, height, seed)
+            && entry.algorithm == algo
+            && maze.has_valid_shape()
+            && (maze.width, maze.height) == (width, height)
+            && maze.fingerprint() == entry.fingerprint)
+            .then_some(maze)
+    }
+
+    /// `load_cached`, or on a miss build the maze with `MazeBuilder` from
+    /// these parameters and `save_cached` it for next time. Generation
+    /// errors such as a zero dimension come back as
+    /// `io::ErrorKind::InvalidInput`.
+    pub fn load_cached_or_generate(
+        dir: &Path,
+        width: usize,
+        height: usize,
+        seed: u64,
+        algo: Algorithm,
+    ) -> io::Result<Maze> {
+        if let Some(maze) = Maze::load_cached(dir, width, height, seed, algo) {
+            return Ok(maze);
+        }
+        let maze = MazeBuilder::new()
+            .width(width)
+            .height(height)
+            .seed(seed)
+            .algorithm(algo)
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        maze.save_cached(dir, algo)?;
+        Ok(maze)
+    }
+
+    /// Whether `vert_walls`/`hor_walls` have the sizes implied by width/height
+    /// and `start`/`end` lie inside a non-empty grid
+    pub(crate) fn has_valid_shape(&self) -> bool {
+        self.width > 0
+            && self.height > 0
+            && self.start.0 < self.width
+            && self.start.1 < self.height
+            && self.end.0 < self.width
+            && self.end.1 < self.height
+            && self.vert_walls.len() == self.height
+            && self
+                .vert_walls
+                .iter()
+                .all(|row| row.len() == self.width + 1)
+            && self.hor_walls.len() == self.height + 1
+            && self.hor_walls.iter().all(|row| row.len() == self.width)
+    }
+}
+
+#[cfg(feature = "ron")]
+impl Maze {
+    /// The maze in RON, laid out for hand editing: every field `save`
+    /// writes, one line per wall row, with struct names and unquoted enum
+    /// variants
+    pub fn to_ron_string(&self) -> String {
+        ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::new()
+                .struct_names(true)
+                .compact_arrays(true),
+        )
+        .expect("a maze always serializes")
+    }
+
+    /// Read a maze written by `to_ron_string`, possibly after hand edits.
+    /// Fails with `MazeError::InvalidRon` when the text does not parse or
+    /// the wall arrays and start/end do not fit the dimensions.
+    pub fn from_ron_str(s: &str) -> Result<Maze, MazeError> {
+        let maze: Maze = ron::from_str(s).map_err(|e| MazeError::InvalidRon(e.to_string()))?;
+        if !maze.has_valid_shape() {
+            return Err(MazeError::InvalidRon(
+                "wall arrays or start/end cells do not match the maze dimensions".into(),
+            ));
+        }
+        Ok(maze)
+    }
+}
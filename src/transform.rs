@@ -0,0 +1,405 @@
+// src/transform.rs
+
+use crate::{Maze, MazeError, Portal, Room, generate::DisjointSet};
+use alloc::vec::Vec;
+use rand::seq::SliceRandom;
+
+impl Maze {
+    /// A copy rotated clockwise (as drawn, with y pointing down) by
+    /// `turns * 90` degrees; `turns` is taken modulo 4. Walls, `start`,
+    /// `end`, rooms, portals, one-way passages, doors, keys and cell
+    /// weights all move with the cells.
+    pub fn rotate(&self, turns: u8) -> Maze {
+        let mut out = self.clone();
+        for _ in 0..turns % 4 {
+            out = out.rotate_cw();
+        }
+        out
+    }
+
+    /// One clockwise quarter turn: old cell (x, y) becomes
+    /// (height - 1 - y, x), and the grid becomes `height` wide
+    fn rotate_cw(&self) -> Maze {
+        let (w, h) = (self.width, self.height);
+        let cell = |(x, y): (usize, usize)| (h - 1 - y, x);
+        // The new west side is the old south side, the new north side the
+        // old west side
+        let vert_walls = (0..w)
+            .map(|ny| (0..=h).map(|nx| self.hor_walls[h - nx][ny]).collect())
+            .collect();
+        let hor_walls = (0..=w)
+            .map(|ny| (0..h).map(|nx| self.vert_walls[h - 1 - nx][ny]).collect())
+            .collect();
+        let mut out = Maze {
+            width: h,
+            height: w,
+            vert_walls,
+            hor_walls,
+            start: cell(self.start),
+            end: cell(self.end),
+            rooms: self
+                .rooms
+                .iter()
+                .map(|r| Room {
+                    x: h - r.y - r.height,
+                    y: r.x,
+                    width: r.height,
+                    height: r.width,
+                })
+                .collect(),
+            portals: self
+                .portals
+                .iter()
+                .map(|p| Portal {
+                    cell_a: cell(p.cell_a),
+                    cell_b: cell(p.cell_b),
+                })
+                .collect(),
+            one_way: self
+                .one_way
+                .iter()
+                .map(|&(a, b)| (cell(a), cell(b)))
+                .collect(),
+            // The split lines would no longer match the halving rule
+            fractal_depth: None,
+            ..self.clone()
+        };
+        self.moved_locks(&mut out, |c| Some(cell(c)));
+        self.moved_weights(&mut out, |c| Some(cell(c)));
+        out
+    }
+
+    /// A copy mirrored left to right
+    pub fn flip_horizontal(&self) -> Maze {
+        let w = self.width;
+        let cell = |(x, y): (usize, usize)| (w - 1 - x, y);
+        let mut out = Maze {
+            vert_walls: self
+                .vert_walls
+                .iter()
+                .map(|row| row.iter().rev().copied().collect())
+                .collect(),
+            hor_walls: self
+                .hor_walls
+                .iter()
+                .map(|row| row.iter().rev().copied().collect())
+                .collect(),
+            start: cell(self.start),
+            end: cell(self.end),
+            rooms: self
+                .rooms
+                .iter()
+                .map(|r| Room {
+                    x: w - r.x - r.width,
+                    ..*r
+                })
+                .collect(),
+            portals: self
+                .portals
+                .iter()
+                .map(|p| Portal {
+                    cell_a: cell(p.cell_a),
+                    cell_b: cell(p.cell_b),
+                })
+                .collect(),
+            one_way: self
+                .one_way
+                .iter()
+                .map(|&(a, b)| (cell(a), cell(b)))
+                .collect(),
+            fractal_depth: None,
+            ..self.clone()
+        };
+        self.moved_locks(&mut out, |c| Some(cell(c)));
+        self.moved_weights(&mut out, |c| Some(cell(c)));
+        out
+    }
+
+    /// A copy mirrored top to bottom
+    pub fn flip_vertical(&self) -> Maze {
+        let h = self.height;
+        let cell = |(x, y): (usize, usize)| (x, h - 1 - y);
+        let mut out = Maze {
+            vert_walls: self.vert_walls.iter().rev().cloned().collect(),
+            hor_walls: self.hor_walls.iter().rev().cloned().collect(),
+            start: cell(self.start),
+            end: cell(self.end),
+            rooms: self
+                .rooms
+                .iter()
+                .map(|r| Room {
+                    y: h - r.y - r.height,
+                    ..*r
+                })
+                .collect(),
+            portals: self
+                .portals
+                .iter()
+                .map(|p| Portal {
+                    cell_a: cell(p.cell_a),
+                    cell_b: cell(p.cell_b),
+                })
+                .collect(),
+            one_way: self
+                .one_way
+                .iter()
+                .map(|&(a, b)| (cell(a), cell(b)))
+                .collect(),
+            fractal_depth: None,
+            ..self.clone()
+        };
+        self.moved_locks(&mut out, |c| Some(cell(c)));
+        self.moved_weights(&mut out, |c| Some(cell(c)));
+        out
+    }
+
+    /// Copy the `w` x `h` region whose top-left cell is (`x`, `y`) into a
+    /// new maze, clipped to this one. Walls along the cut edges are solid
+    /// even where a passage crossed them. `start`/`end` are kept when they
+    /// fall inside the region, otherwise they reset to the corners; only
+    /// rooms lying wholly inside, keys and cell weights inside, and
+    /// portals, one-way passages and doors with both ends inside, are
+    /// kept. Fails with `MazeError::ZeroDimension` if nothing of the region
+    /// is left.
+    pub fn sub_maze(&self, x: usize, y: usize, w: usize, h: usize) -> Result<Maze, MazeError> {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let w = w.min(self.width - x);
+        let h = h.min(self.height - y);
+        let mut out = Maze::new(w, h)?;
+        out.seed = self.seed;
+        for ny in 0..h {
+            for nx in 1..w {
+                out.vert_walls[ny][nx] = self.vert_walls[y + ny][x + nx];
+            }
+        }
+        for ny in 1..h {
+            for nx in 0..w {
+                out.hor_walls[ny][nx] = self.hor_walls[y + ny][x + nx];
+            }
+        }
+        let local = |(cx, cy): (usize, usize)| {
+            (cx >= x && cx < x + w && cy >= y && cy < y + h).then(|| (cx - x, cy - y))
+        };
+        if let Some(start) = local(self.start) {
+            out.start = start;
+        }
+        if let Some(end) = local(self.end) {
+            out.end = end;
+        }
+        out.rooms = self
+            .rooms
+            .iter()
+            .filter(|r| r.x >= x && r.y >= y && r.x + r.width <= x + w && r.y + r.height <= y + h)
+            .map(|r| Room {
+                x: r.x - x,
+                y: r.y - y,
+                ..*r
+            })
+            .collect();
+        out.portals = self
+            .portals
+            .iter()
+            .filter_map(|p| {
+                Some(Portal {
+                    cell_a: local(p.cell_a)?,
+                    cell_b: local(p.cell_b)?,
+                })
+            })
+            .collect();
+        out.one_way = self
+            .one_way
+            .iter()
+            .filter_map(|&(a, b)| Some((local(a)?, local(b)?)))
+            .collect();
+        self.moved_locks(&mut out, local);
+        self.moved_weights(&mut out, local);
+        Ok(out)
+    }
+
+    /// Cut the maze into the columns left of `col` and the columns from
+    /// `col` on, as two `sub_maze`s: the cut line becomes a solid border in
+    /// both halves, so a half of a perfect maze can fall apart into several
+    /// regions. Fails with `MazeError::OutOfBounds` at (`col`, 0) unless
+    /// `0 < col < width`.
+    pub fn split_at_column(&self, col: usize) -> Result<(Maze, Maze), MazeError> {
+        if col == 0 || col >= self.width {
+            return Err(MazeError::OutOfBounds { x: col, y: 0 });
+        }
+        let left = self.sub_maze(0, 0, col, self.height)?;
+        let right = self.sub_maze(col, 0, self.width - col, self.height)?;
+        Ok((left, right))
+    }
+
+    /// Cut the maze into the rows above `row` and the rows from `row` on;
+    /// see `split_at_column`. Fails with `MazeError::OutOfBounds` at
+    /// (0, `row`) unless `0 < row < height`.
+    pub fn split_at_row(&self, row: usize) -> Result<(Maze, Maze), MazeError> {
+        if row == 0 || row >= self.height {
+            return Err(MazeError::OutOfBounds { x: 0, y: row });
+        }
+        let top = self.sub_maze(0, 0, self.width, row)?;
+        let bottom = self.sub_maze(0, row, self.width, self.height - row)?;
+        Ok((top, bottom))
+    }
+
+    /// Paste `other`'s walls and rooms into this maze with its top-left cell
+    /// at `offset`, then open random walls along the edge of the pasted
+    /// region, one per pair of regions they join, until every part that
+    /// touches the region is connected again. Two perfect mazes merge into
+    /// a perfect maze. Fails with `MazeError::OutOfBounds`, naming the cell
+    /// `other`'s bottom-right corner would land on, if it does not fit.
+    pub fn merge(&mut self, other: &Maze, offset: (usize, usize)) -> Result<(), MazeError> {
+        let (ox, oy) = offset;
+        let (w, h) = (other.width, other.height);
+        if ox + w > self.width || oy + h > self.height {
+            return Err(MazeError::OutOfBounds {
+                x: (ox + w).saturating_sub(1),
+                y: (oy + h).saturating_sub(1),
+            });
+        }
+        for y in 0..h {
+            self.vert_walls[oy + y][ox..=ox + w].copy_from_slice(&other.vert_walls[y]);
+        }
+        for y in 0..=h {
+            self.hor_walls[oy + y][ox..ox + w].copy_from_slice(&other.hor_walls[y]);
+        }
+
+        // Pasting cuts the old passages through the region; reconnect across
+        // its edge with one random wall per merge
+        let inside =
+            |(x, y): (usize, usize)| (ox..ox + w).contains(&x) && (oy..oy + h).contains(&y);
+        let width = self.width;
+        let idx = move |(x, y): (usize, usize)| y * width + x;
+        let mut sets = DisjointSet::new(self.width * self.height);
+        let mut walls = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                for n in [(x + 1, y), (x, y + 1)] {
+                    if !self.in_bounds(n) {
+                        continue;
+                    }
+                    if self.open_neighbors((x, y)).contains(&n) {
+                        sets.union(idx((x, y)), idx(n));
+                    } else if inside((x, y)) != inside(n) {
+                        walls.push(((x, y), n));
+                    }
+                }
+            }
+        }
+        walls.shuffle(&mut self.seeded_rng());
+        for (a, b) in walls {
+            if sets.union(idx(a), idx(b)) {
+                self.carve(a, b);
+            }
+        }
+        self.rooms.extend(other.rooms.iter().map(|r| Room {
+            x: r.x + ox,
+            y: r.y + oy,
+            ..*r
+        }));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Maze, MazeError};
+
+    fn generated() -> Maze {
+        generated_with_seed(3)
+    }
+
+    fn generated_with_seed(seed: u64) -> Maze {
+        let mut maze = Maze::new(6, 5).unwrap();
+        maze.generate_with_seed(seed);
+        maze
+    }
+
+    fn braided(seed: u64) -> Maze {
+        let mut maze = generated_with_seed(seed);
+        maze.add_loops(0.2, None);
+        maze
+    }
+
+    #[test]
+    fn four_quarter_turns_are_the_identity() {
+        for seed in 0..5 {
+            let maze = braided(seed);
+            assert_eq!(maze.rotate(4), maze, "seed {seed}");
+            assert_eq!(maze.rotate(1).rotate(3), maze, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn half_turn_is_both_flips() {
+        for seed in 0..5 {
+            let maze = braided(seed);
+            let flipped = maze.flip_horizontal().flip_vertical();
+            assert_eq!(maze.rotate(2), flipped, "seed {seed}");
+            assert_eq!(flipped.flip_vertical().flip_horizontal(), maze);
+        }
+    }
+
+    #[test]
+    fn quarter_turn_keeps_a_perfect_maze_perfect() {
+        for seed in 0..5 {
+            let maze = generated_with_seed(seed);
+            let turned = maze.rotate(1);
+            assert_eq!((turned.width, turned.height), (5, 6));
+            assert!(turned.is_perfect(), "seed {seed}");
+            assert_eq!(
+                turned.solve().unwrap().len(),
+                maze.solve().unwrap().len(),
+                "seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn split_at_column_walls_off_the_cut() {
+        let mut maze = generated();
+        // Open the whole cut line so both borders have to be raised
+        for y in 0..5 {
+            maze.vert_walls[y][2] = false;
+        }
+        let (left, right) = maze.split_at_column(2).unwrap();
+        assert_eq!((left.width, right.width), (2, 4));
+        for y in 0..5 {
+            assert!(left.vert_walls[y][2] && right.vert_walls[y][0]);
+            assert_eq!(left.vert_walls[y][1], maze.vert_walls[y][1]);
+            assert_eq!(right.vert_walls[y][1..4], maze.vert_walls[y][3..6]);
+        }
+        assert_eq!(left.hor_walls[3][..], maze.hor_walls[3][..2]);
+        assert_eq!(right.hor_walls[3][..], maze.hor_walls[3][2..]);
+    }
+
+    #[test]
+    fn split_at_row_walls_off_the_cut() {
+        let mut maze = generated();
+        maze.hor_walls[4].fill(false);
+        let (top, bottom) = maze.split_at_row(4).unwrap();
+        assert_eq!((top.height, bottom.height), (4, 1));
+        assert!(top.hor_walls[4].iter().all(|&w| w));
+        assert!(bottom.hor_walls[0].iter().all(|&w| w));
+        assert_eq!(top.hor_walls[1..4], maze.hor_walls[1..4]);
+        assert_eq!(bottom.vert_walls[0], maze.vert_walls[4]);
+    }
+
+    #[test]
+    fn split_outside_the_grid_fails() {
+        let maze = generated();
+        for col in [0, 6, 9] {
+            assert_eq!(
+                maze.split_at_column(col).unwrap_err(),
+                MazeError::OutOfBounds { x: col, y: 0 }
+            );
+        }
+        for row in [0, 5] {
+            assert_eq!(
+                maze.split_at_row(row).unwrap_err(),
+                MazeError::OutOfBounds { x: 0, y: row }
+            );
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// src/wasm.rs
+
+use crate::{DrawOptions, MapOptions, Maze};
+use image::DynamicImage;
+use wasm_bindgen::{Clamped, JsError, prelude::wasm_bindgen};
+
+/// `Maze` for JavaScript, built with the `wasm` feature
+#[wasm_bindgen(js_name = Maze)]
+pub struct WasmMaze(Maze);
+
+#[wasm_bindgen(js_class = Maze)]
+impl WasmMaze {
+    /// A `width` x `height` grid with every wall standing
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize) -> Result<WasmMaze, JsError> {
+        Maze::new(width, height)
+            .map(WasmMaze)
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Carve the maze with the randomized depth-first search
+    pub fn generate(&mut self) {
+        self.0.generate();
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> usize {
+        self.0.width
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> usize {
+        self.0.height
+    }
+
+    /// The solution from `start` to `end` as flat `[x0, y0, x1, y1, ...]`
+    pub fn solve(&self) -> Result<Vec<u32>, JsError> {
+        let path = self.0.solve().map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(path
+            .into_iter()
+            .flat_map(|(x, y)| [x as u32, y as u32])
+            .collect())
+    }
+
+    /// See `Maze::to_ascii`
+    pub fn to_ascii(&self) -> String {
+        self.0.to_ascii()
+    }
+
+    /// The Krunker map from `Maze::to_map_json` with default options, as a
+    /// JSON string
+    pub fn to_map_json(&self) -> String {
+        self.0.to_map_json(&MapOptions::default()).to_string()
+    }
+
+    /// The maze and its solution as raw RGBA pixels, ready for `ImageData`.
+    /// The image is `width * cell_size + wall_thickness` pixels wide and
+    /// `height * cell_size + wall_thickness` tall.
+    pub fn draw(&self, cell_size: usize, wall_thickness: usize) -> Clamped<Vec<u8>> {
+        let opts = DrawOptions {
+            cell_size,
+            wall_thickness,
+            ..DrawOptions::default()
+        };
+        Clamped(
+            DynamicImage::ImageRgb8(self.0.draw(&opts))
+                .into_rgba8()
+                .into_raw(),
+        )
+    }
+}
@@ -0,0 +1,185 @@
+// src/weights.rs
+
+use crate::{Maze, MazeError};
+use alloc::{vec, vec::Vec};
+use serde::{Deserialize, Serialize};
+
+/// A rectangle of cells sharing one movement cost, as read from a weight
+/// map file by `--weights`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeightZone {
+    pub x: usize,
+    pub y: usize,
+    #[serde(default = "one")]
+    pub width: usize,
+    #[serde(default = "one")]
+    pub height: usize,
+    pub weight: u32,
+}
+
+fn one() -> usize {
+    1
+}
+
+impl Maze {
+    /// Make moving into cell (`x`, `y`) cost `weight` for `solve` and
+    /// `solve_between` instead of 1: higher for mud or hazards, 0 for a
+    /// boost. Fails with `MazeError::OutOfBounds` when the cell is outside
+    /// the grid.
+    pub fn set_cell_weight(&mut self, x: usize, y: usize, weight: u32) -> Result<(), MazeError> {
+        if !self.in_bounds((x, y)) {
+            return Err(MazeError::OutOfBounds { x, y });
+        }
+        if self.cell_weights.is_empty() {
+            self.cell_weights = vec![vec![1; self.width]; self.height];
+        }
+        self.cell_weights[y][x] = weight;
+        Ok(())
+    }
+
+    /// Cost of moving into cell (`x`, `y`); 1 unless changed by
+    /// `set_cell_weight`
+    pub fn cell_weight(&self, x: usize, y: usize) -> u32 {
+        self.cell_weights
+            .get(y)
+            .and_then(|row| row.get(x))
+            .copied()
+            .unwrap_or(1)
+    }
+
+    /// `set_cell_weight` on every cell of each zone in turn, so later
+    /// zones win where they overlap. Fails with `MazeError::OutOfBounds`,
+    /// changing nothing, when a zone reaches outside the grid.
+    pub fn apply_weight_zones(&mut self, zones: &[WeightZone]) -> Result<(), MazeError> {
+        for zone in zones {
+            let (x, y) = (
+                zone.x + zone.width.saturating_sub(1),
+                zone.y + zone.height.saturating_sub(1),
+            );
+            if !self.in_bounds((x, y)) {
+                return Err(MazeError::OutOfBounds { x, y });
+            }
+        }
+        for zone in zones {
+            for y in zone.y..zone.y + zone.height {
+                for x in zone.x..zone.x + zone.width {
+                    self.set_cell_weight(x, y, zone.weight)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The lowest and highest cell weight; `None` while every cell costs 1
+    pub(crate) fn weight_range(&self) -> Option<(u32, u32)> {
+        let mut weights = self.cell_weights.iter().flatten().copied();
+        let first = weights.next()?;
+        Some(weights.fold((first, first), |(lo, hi), w| (lo.min(w), hi.max(w))))
+    }
+
+    /// `cell_weights` carried over to `out`, a transformed copy of this
+    /// maze, with `cell` mapping old cells to new ones
+    pub(crate) fn moved_weights(
+        &self,
+        out: &mut Maze,
+        cell: impl Fn((usize, usize)) -> Option<(usize, usize)>,
+    ) {
+        out.cell_weights = Vec::new();
+        for (y, row) in self.cell_weights.iter().enumerate() {
+            for (x, &weight) in row.iter().enumerate() {
+                if weight != 1
+                    && let Some((nx, ny)) = cell((x, y))
+                {
+                    // In bounds by construction of `cell`
+                    let _ = out.set_cell_weight(nx, ny, weight);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WeightZone;
+    use crate::{Maze, MazeError};
+    use alloc::vec;
+
+    /// A 3x2 ring of passages from the start (0, 0) to the end (2, 0): two
+    /// moves straight along the top row, or four round the bottom
+    fn ring() -> Maze {
+        let mut maze = Maze::new(3, 2).unwrap();
+        for (a, b) in [
+            ((0, 0), (1, 0)),
+            ((1, 0), (2, 0)),
+            ((0, 0), (0, 1)),
+            ((0, 1), (1, 1)),
+            ((1, 1), (2, 1)),
+            ((2, 1), (2, 0)),
+        ] {
+            maze.carve(a, b);
+        }
+        maze.end = (2, 0);
+        maze
+    }
+
+    #[test]
+    fn heavy_cell_sends_the_path_the_long_way() {
+        let mut maze = ring();
+        assert_eq!(maze.solve(), Ok(vec![(0, 0), (1, 0), (2, 0)]));
+        maze.set_cell_weight(1, 0, 10).unwrap();
+        assert_eq!(
+            maze.solve(),
+            Ok(vec![(0, 0), (0, 1), (1, 1), (2, 1), (2, 0)])
+        );
+        assert_eq!(maze.weight_range(), Some((1, 10)));
+    }
+
+    #[test]
+    fn boost_cells_cost_nothing() {
+        let mut maze = ring();
+        let boost = WeightZone {
+            x: 0,
+            y: 1,
+            width: 3,
+            height: 1,
+            weight: 0,
+        };
+        maze.apply_weight_zones(&[boost]).unwrap();
+        assert_eq!(maze.cell_weight(1, 1), 0);
+        // Three free cells and the end beat the direct route's two cells
+        assert_eq!(
+            maze.solve(),
+            Ok(vec![(0, 0), (0, 1), (1, 1), (2, 1), (2, 0)])
+        );
+        assert_eq!(maze.weight_range(), Some((0, 1)));
+    }
+
+    #[test]
+    fn later_zones_win_and_bad_zones_change_nothing() {
+        let mut maze = ring();
+        assert_eq!(maze.weight_range(), None);
+        let zone = |x, y, width, height, weight| WeightZone {
+            x,
+            y,
+            width,
+            height,
+            weight,
+        };
+        assert_eq!(
+            maze.apply_weight_zones(&[zone(0, 0, 2, 2, 5), zone(2, 1, 2, 1, 3)]),
+            Err(MazeError::OutOfBounds { x: 3, y: 1 })
+        );
+        assert_eq!(maze.weight_range(), None);
+        assert_eq!(
+            maze.set_cell_weight(0, 2, 4),
+            Err(MazeError::OutOfBounds { x: 0, y: 2 })
+        );
+
+        maze.apply_weight_zones(&[zone(0, 0, 2, 2, 5), zone(1, 1, 1, 1, 3)])
+            .unwrap();
+        assert_eq!(maze.cell_weight(0, 1), 5);
+        assert_eq!(maze.cell_weight(1, 1), 3);
+        assert_eq!(maze.cell_weight(2, 0), 1);
+        assert_eq!(maze.weight_range(), Some((1, 5)));
+    }
+}